@@ -1,8 +1,16 @@
+use crate::settings::config::SharedSettings;
+
 pub struct App {
+    pub settings: SharedSettings,
+    /// Shown as toasts once the first screen is up, e.g. problems with config files
+    pub startup_messages: Vec<String>,
 }
 
 impl App {
-    pub fn new() -> App {
-        App {}
+    pub fn new(settings: SharedSettings) -> App {
+        App {
+            settings,
+            startup_messages: vec![],
+        }
     }
-}
\ No newline at end of file
+}
@@ -13,3 +13,10 @@ pub const TITLE: &str = concat!(
 "                                                                                                \\______/                               \n",
 );
 
+
+/// Stands in for `TITLE` on terminals too small for it
+pub const COMPACT_TITLE: &str = concat!(
+"\n",
+"♠ ♥ ♦ ♣\n",
+"JACK'S BLACKJACK\n",
+);
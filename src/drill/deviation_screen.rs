@@ -0,0 +1,263 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::rc::Rc;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::prelude::{Line, Span, Stylize};
+use blackjack_engine::card::Card;
+use crate::drill::index_table_screen::{IndexTableScreen, SharedDeviations};
+use crate::drill::schedule::Flashcard;
+use crate::event::AppEvent;
+use crate::game::deviations::{Deviation, DeviationSet, DeviationTable};
+use crate::game::rules::HouseRules;
+use crate::game::strategy::Play;
+use crate::game::trainer::Score;
+use crate::keybindings::{bindings, Action, Context};
+use crate::menu::menu_widget::{Menu, MenuEvent, MenuItem};
+use crate::model::{Model, ModelResponse};
+use crate::navigation::Navigation;
+use crate::overlay::help::HelpOverlay;
+use crate::rng::Rng;
+use crate::settings::config::Settings;
+use crate::theme::{theme, Slot};
+use crate::ui::{card_span, render_border, render_footer_hints, render_line, render_text, FooterHint};
+
+/// How far from the index the true count of a scenario lands, either side
+const COUNT_SPREAD: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DeviationItem {
+    Practice,
+    IndexTable,
+}
+
+impl fmt::Display for DeviationItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            DeviationItem::Practice => "Practice",
+            DeviationItem::IndexTable => "Index table",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// A deviation hand dealt at a true count on one side of its index
+#[derive(Clone, Debug)]
+struct Scenario {
+    deviation: Deviation,
+    true_count: i32,
+    hand: Vec<Card>,
+    dealer_up: Card,
+}
+
+/// Deals the hands of the Illustrious 18 and Fab 4 at true counts just
+/// either side of their index, and grades whether the player deviated.
+pub struct DeviationScreen {
+    menu: Menu<DeviationItem>,
+    table: SharedDeviations,
+    /// Surrender is offered, the Fab 4 are all surrenders
+    rules: HouseRules,
+    rng: Rng,
+    /// Set while practising, `None` on the menu
+    scenario: Option<Scenario>,
+    scores: BTreeMap<DeviationSet, Score>,
+    /// How the last answer went
+    feedback: Option<Line<'static>>,
+}
+
+impl DeviationScreen {
+    pub fn new(settings: &Settings) -> DeviationScreen {
+        let (table, problem) = DeviationTable::load(settings.storage_dir());
+        DeviationScreen {
+            menu: Menu::new(vec![
+                MenuItem::new(DeviationItem::Practice, DeviationItem::Practice.to_string())
+                    .hotkey('p')
+                    .description("Hands dealt at a true count either side of their index"),
+                MenuItem::new(DeviationItem::IndexTable, DeviationItem::IndexTable.to_string())
+                    .hotkey('i')
+                    .description("Change the index of any play to the ones you learned"),
+            ]),
+            table: Rc::new(RefCell::new(table)),
+            rules: HouseRules { decks: settings.decks(), surrender: true, ..HouseRules::default() },
+            rng: settings.rng(),
+            scenario: None,
+            scores: BTreeMap::new(),
+            feedback: problem.map(|problem| Line::from(problem).fg(theme().color(Slot::Loss))),
+        }
+    }
+
+    fn deal(&mut self) {
+        let entries = self.table.borrow().entries();
+        let deviation = *self.rng.pick(&entries);
+        // Half the time the index is reached, half the time it isn't
+        let reached = self.rng.below(2) == 0;
+        let distance = self.rng.below(COUNT_SPREAD) as i32;
+        let true_count = match reached == deviation.at_or_above {
+            true => deviation.index + distance,
+            false => deviation.index - 1 - distance,
+        };
+        let card = Flashcard { category: deviation.category, total: deviation.total, dealer_up: deviation.dealer_up };
+        let (hand, dealer_up) = card.deal(&mut self.rng);
+        self.scenario = Some(Scenario { deviation, true_count, hand, dealer_up });
+    }
+
+    fn answer(&mut self, played: Play) -> ModelResponse {
+        let Some(scenario) = &self.scenario else {
+            return ModelResponse::NoOp;
+        };
+        let deviation = scenario.deviation;
+        let best = deviation.correct_play(scenario.true_count, &self.rules);
+        let correct = played == best;
+        self.scores.entry(deviation.set).or_default().add(correct);
+        let at = format!("{} at {:+}", deviation.name(), scenario.true_count);
+        self.feedback = Some(if correct {
+            Line::from(format!("Correct, {}: {}", at, best)).fg(theme().color(Slot::Win))
+        } else {
+            Line::from(format!("{} was wrong. {}: {}, the index says {}", played, at, best, deviation.rule().to_lowercase()))
+                .fg(theme().color(Slot::Loss))
+        });
+        self.deal();
+        ModelResponse::Refresh
+    }
+
+    fn score_line(&self) -> String {
+        [DeviationSet::Illustrious18, DeviationSet::Fab4]
+            .map(|set| format!("{}: {}", set, self.scores.get(&set).copied().unwrap_or_default()))
+            .join("    ")
+    }
+
+    fn footer_hints(&self) -> Vec<FooterHint> {
+        let actions = match self.scenario {
+            Some(_) => vec![Action::Hit, Action::Stand, Action::Double, Action::Split, Action::Surrender, Action::Back, Action::Help],
+            None => vec![Action::Select, Action::Back, Action::Help],
+        };
+        actions.into_iter().map(FooterHint::action).collect()
+    }
+
+    fn handle_practice(&mut self, event: &AppEvent) -> ModelResponse {
+        let AppEvent::Key(key) = event else {
+            return ModelResponse::NoOp;
+        };
+        match bindings().action(Context::PlayerTurn, key) {
+            Some(Action::Hit) => self.answer(Play::Hit),
+            Some(Action::Stand) => self.answer(Play::Stand),
+            Some(Action::Double) => self.answer(Play::Double),
+            Some(Action::Split) => self.answer(Play::Split),
+            Some(Action::Surrender) => self.answer(Play::Surrender),
+            // Back to the menu of this screen
+            Some(Action::Back) => {
+                self.scenario = None;
+                ModelResponse::Refresh
+            }
+            Some(Action::MainMenu) => ModelResponse::Navigate(Navigation::PopToRoot),
+            Some(Action::Quit) => ModelResponse::Exit,
+            _ => ModelResponse::NoOp,
+        }
+    }
+
+    fn render_scenario(&self, frame: &mut Frame, layout: &[Rect], scenario: &Scenario) {
+        render_line(frame, layout[1], Line::from(format!("True count {:+}", scenario.true_count)).bold().centered());
+        render_line(frame, layout[3], Line::from(vec![Span::raw("Dealer shows "), card_span(&scenario.dealer_up)]).centered());
+        let mut hand = vec![Span::raw("You have ")];
+        for card in &scenario.hand {
+            hand.push(card_span(card));
+            hand.push(Span::raw(" "));
+        }
+        render_line(frame, layout[5], Line::from(hand).centered());
+    }
+}
+
+impl Model for DeviationScreen {
+    fn update(&mut self, event: &AppEvent) -> std::io::Result<ModelResponse> {
+        if self.scenario.is_some() {
+            return Ok(self.handle_practice(event));
+        }
+        match self.menu.handle(event) {
+            MenuEvent::Activated(DeviationItem::Practice) | MenuEvent::Clicked(DeviationItem::Practice, _) => {
+                self.deal();
+                return Ok(ModelResponse::Refresh);
+            }
+            MenuEvent::Activated(DeviationItem::IndexTable) | MenuEvent::Clicked(DeviationItem::IndexTable, _) => {
+                let screen = IndexTableScreen::new(self.table.clone());
+                return Ok(ModelResponse::Navigate(Navigation::Push(Box::new(screen))));
+            }
+            MenuEvent::Moved => return Ok(ModelResponse::Refresh),
+            MenuEvent::Disabled(_) => return Ok(ModelResponse::NoOp),
+            MenuEvent::Ignored => {}
+        }
+        let AppEvent::Key(key) = event else {
+            return Ok(ModelResponse::NoOp);
+        };
+        match bindings().action(Context::Menu, key) {
+            Some(Action::Quit) => Ok(ModelResponse::Exit),
+            Some(Action::MainMenu) => Ok(ModelResponse::Navigate(Navigation::PopToRoot)),
+            Some(Action::Back) => Ok(ModelResponse::Navigate(Navigation::Pop)),
+            _ => Ok(ModelResponse::NoOp),
+        }
+    }
+
+    fn ui(&mut self, frame: &mut Frame) {
+        let screen = frame.area();
+        render_border(frame, screen);
+        render_text(frame, screen, " Index Plays ");
+
+        let inner = Rect::new(screen.x + 1, screen.y + 1, screen.width.saturating_sub(2), screen.height.saturating_sub(2));
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(1),  // True count, or the menu's first line
+                Constraint::Length(1),
+                Constraint::Length(1),  // Dealer up card
+                Constraint::Length(1),
+                Constraint::Length(1),  // Player hand
+                Constraint::Length(2),
+                Constraint::Length(1),  // Feedback
+                Constraint::Fill(1),
+                Constraint::Length(1),  // Scores
+                Constraint::Length(1),
+                Constraint::Length(1),  // Footer
+            ])
+            .split(inner);
+
+        match &self.scenario {
+            Some(scenario) => self.render_scenario(frame, &layout, scenario),
+            None => {
+                self.menu.render(frame, Rect { height: 4, ..layout[1] });
+                self.menu.render_description(frame, layout[5]);
+            }
+        }
+        if let Some(feedback) = &self.feedback {
+            render_line(frame, layout[7], feedback.clone().centered());
+        }
+        render_text(frame, layout[9], &self.score_line());
+        render_footer_hints(frame, &self.footer_hints(), layout[11]);
+    }
+
+    fn help(&self) -> HelpOverlay {
+        let context = if self.scenario.is_some() { Context::PlayerTurn } else { Context::Menu };
+        HelpOverlay::new(context, &self.rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyCode;
+    use crate::testing::{Harness, ScriptedEvents};
+
+    #[test]
+    fn index_plays_are_graded() {
+        let mut harness = Harness::new();
+        harness.run(ScriptedEvents::new().type_text("vp"));
+        harness.assert_contains("True count ");
+        harness.run(ScriptedEvents::new().type_text("vps"));
+        // The seeded first hand is 16 against a 10, at the index where it stands
+        harness.assert_contains("Correct, 16 vs 10 at +0: Stand");
+        harness.assert_contains("1/1 (100%)");
+
+        // Esc leaves practice for the menu of the screen
+        harness.run(ScriptedEvents::new().type_text("vp").key(KeyCode::Esc));
+        harness.assert_contains("> Practice");
+    }
+}
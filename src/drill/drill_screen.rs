@@ -0,0 +1,218 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::prelude::{Line, Span, Stylize};
+use blackjack_engine::card::Card;
+use crate::drill::schedule::{DrillProgress, DrillSession, Flashcard};
+use crate::event::{AppEvent, TICK_RATE};
+use crate::game::rules::HouseRules;
+use crate::game::strategy::{best_play, explain, Play};
+use crate::keybindings::{bindings, Action, Context};
+use crate::model::{Model, ModelResponse};
+use crate::navigation::Navigation;
+use crate::overlay::help::HelpOverlay;
+use crate::rng::Rng;
+use crate::settings::config::Settings;
+use crate::theme::{theme, Slot};
+use crate::ui::{card_span, render_border, render_footer_hints, render_line, render_text, FooterHint};
+
+/// Past sessions shown under the flashcard
+const SHOWN_SESSIONS: usize = 5;
+
+/// A hand and a dealer up card, nothing else. The answer is graded straight
+/// away, and cards answered wrong or slowly come back sooner.
+pub struct DrillScreen {
+    progress: DrillProgress,
+    rules: HouseRules,
+    rng: Rng,
+    card: Flashcard,
+    hand: Vec<Card>,
+    dealer_up: Card,
+    /// Ticks since the current card was shown, to time the answer
+    ticks: u64,
+    /// How the last answer went
+    feedback: Option<Line<'static>>,
+}
+
+impl DrillScreen {
+    pub fn new(settings: &Settings) -> DrillScreen {
+        let (mut progress, problem) = DrillProgress::load(settings.storage_dir());
+        progress.start_session();
+        let mut rng = Rng::from_time();
+        let card = progress.next_card(&mut rng);
+        let (hand, dealer_up) = card.deal(&mut rng);
+        DrillScreen {
+            progress,
+            rules: HouseRules { decks: settings.decks(), ..HouseRules::default() },
+            rng,
+            card,
+            hand,
+            dealer_up,
+            ticks: 0,
+            // A broken progress file is started over, say so where the feedback goes
+            feedback: problem.map(|problem| Line::from(problem).fg(theme().color(Slot::Loss))),
+        }
+    }
+
+    fn next_card(&mut self) {
+        self.card = self.progress.next_card(&mut self.rng);
+        (self.hand, self.dealer_up) = self.card.deal(&mut self.rng);
+        self.ticks = 0;
+    }
+
+    fn answer(&mut self, played: Play) -> ModelResponse {
+        let situation = self.card.situation();
+        let best = best_play(&situation, &self.rules);
+        let millis = self.ticks * TICK_RATE.as_millis() as u64;
+        let correct = played == best;
+        self.feedback = Some(if correct {
+            Line::from(format!("Correct, {} in {:.1}s", best, millis as f64 / 1000.0)).fg(theme().color(Slot::Win))
+        } else {
+            Line::from(format!("{} was wrong. {}", played, explain(&situation, best))).fg(theme().color(Slot::Loss))
+        });
+        let saved = self.progress.record(self.card, correct, millis);
+        self.next_card();
+        match saved {
+            Ok(()) => ModelResponse::Refresh,
+            Err(err) => ModelResponse::Toast(format!("Drill progress not saved: {}", err)),
+        }
+    }
+
+    fn footer_hints(&self) -> Vec<FooterHint> {
+        [Action::Hit, Action::Stand, Action::Double, Action::Split, Action::Back, Action::Help]
+            .into_iter()
+            .map(FooterHint::action)
+            .collect()
+    }
+
+    /// This session and the ones before it, with arrows showing the trend
+    fn session_lines(&self) -> Vec<Line<'static>> {
+        let sessions = self.progress.sessions();
+        let mut lines = vec![Line::from("Sessions").bold().underlined()];
+        let skip = sessions.len().saturating_sub(SHOWN_SESSIONS);
+        for (i, session) in sessions.iter().enumerate().skip(skip).rev() {
+            let previous = i.checked_sub(1).map(|previous| &sessions[previous]);
+            let label = if i + 1 == sessions.len() { "This session".to_string() } else { format!("Session {}", i + 1) };
+            lines.push(Line::from(format!("{:<13}{}", label, describe_session(session, previous))));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("Due for review: {}", self.progress.due_count())));
+        lines
+    }
+}
+
+/// "20 answers  85% ↑  1.6s ↓", the arrows compare with `previous`
+fn describe_session(session: &DrillSession, previous: Option<&DrillSession>) -> String {
+    let (Some(accuracy), Some(average)) = (session.accuracy(), session.average_ms()) else {
+        return "no answers yet".to_string();
+    };
+    let trend = |now: f64, before: Option<f64>, higher_is_better: bool| match before {
+        Some(before) if (now > before) == higher_is_better && now != before => " ↑",
+        Some(before) if now != before => " ↓",
+        _ => "",
+    };
+    let previous_accuracy = previous.and_then(DrillSession::accuracy);
+    let previous_average = previous.and_then(DrillSession::average_ms).map(|ms| ms as f64);
+    format!(
+        "{} answers  {:.0}%{}  {:.1}s{}",
+        session.answers,
+        accuracy,
+        trend(accuracy, previous_accuracy, true),
+        average as f64 / 1000.0,
+        trend(average as f64, previous_average, false),
+    )
+}
+
+impl Model for DrillScreen {
+    fn update(&mut self, event: &AppEvent) -> std::io::Result<ModelResponse> {
+        if *event == AppEvent::Tick {
+            self.ticks += 1;
+            return Ok(ModelResponse::NoOp);
+        }
+        let AppEvent::Key(key) = event else {
+            return Ok(ModelResponse::NoOp);
+        };
+        match bindings().action(Context::PlayerTurn, key) {
+            Some(Action::Hit) => Ok(self.answer(Play::Hit)),
+            Some(Action::Stand) => Ok(self.answer(Play::Stand)),
+            Some(Action::Double) => Ok(self.answer(Play::Double)),
+            Some(Action::Split) => Ok(self.answer(Play::Split)),
+            Some(Action::Quit) => Ok(ModelResponse::Exit),
+            Some(Action::MainMenu) => Ok(ModelResponse::Navigate(Navigation::PopToRoot)),
+            Some(Action::Back) => Ok(ModelResponse::Navigate(Navigation::Pop)),
+            _ => Ok(ModelResponse::NoOp),
+        }
+    }
+
+    fn ui(&mut self, frame: &mut Frame) {
+        let screen = frame.area();
+        render_border(frame, screen);
+        render_text(frame, screen, " Strategy Drill ");
+
+        let inner = Rect::new(screen.x + 1, screen.y + 1, screen.width.saturating_sub(2), screen.height.saturating_sub(2));
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(1),  // Dealer up card
+                Constraint::Length(1),
+                Constraint::Length(1),  // Player hand
+                Constraint::Length(2),
+                Constraint::Length(1),  // Feedback
+                Constraint::Fill(1),
+                Constraint::Length(SHOWN_SESSIONS as u16 + 3),
+                Constraint::Length(1),  // Footer
+            ])
+            .split(inner);
+
+        render_line(frame, layout[1], Line::from(vec![Span::raw("Dealer shows "), card_span(&self.dealer_up)]).centered());
+        let mut hand = vec![Span::raw("You have ")];
+        for card in &self.hand {
+            hand.push(card_span(card));
+            hand.push(Span::raw(" "));
+        }
+        render_line(frame, layout[3], Line::from(hand).centered());
+        if let Some(feedback) = &self.feedback {
+            render_line(frame, layout[5], feedback.clone().centered());
+        }
+
+        let sessions = centered_column(layout[7], 50);
+        for (row, line) in self.session_lines().into_iter().enumerate() {
+            let rect = Rect { y: sessions.y + row as u16, height: 1, ..sessions };
+            render_line(frame, rect.intersection(layout[7]), line);
+        }
+        render_footer_hints(frame, &self.footer_hints(), layout[8]);
+    }
+
+    fn help(&self) -> HelpOverlay {
+        HelpOverlay::new(Context::PlayerTurn, &self.rules)
+    }
+}
+
+/// A column `width` wide in the middle of `rect`
+fn centered_column(rect: Rect, width: u16) -> Rect {
+    let width = width.min(rect.width);
+    Rect { x: rect.x + (rect.width - width) / 2, width, ..rect }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyCode;
+    use crate::testing::{Harness, ScriptedEvents};
+
+    #[test]
+    fn answers_are_graded_and_counted() {
+        let mut harness = Harness::new();
+        harness.run(ScriptedEvents::new().type_text("d").ticks(10).type_text("s"));
+        harness.assert_contains("Strategy Drill");
+        harness.assert_contains("This session 1 answers");
+        let screen = harness.screen_text();
+        assert!(screen.contains("Correct, Stand") || screen.contains("Stand was wrong"), "{}", screen);
+    }
+
+    #[test]
+    fn back_returns_to_the_menu() {
+        let mut harness = Harness::new();
+        harness.run(ScriptedEvents::new().type_text("d").key(KeyCode::Esc));
+        harness.assert_contains("> Drill");
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+
+/// The best count down times of the count flash drill, saved to `flash.json`.
+/// How the drill is set up is kept with the other settings.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlashRecords {
+    /// Fastest correct count down a deck per counting system, in milliseconds
+    best_count_down: BTreeMap<String, u64>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl FlashRecords {
+    /// Reads the records in `dir`, or starts afresh. Without a directory nothing is saved.
+    pub fn load(dir: Option<PathBuf>) -> (FlashRecords, Option<String>) {
+        let Some(path) = dir.map(|dir| dir.join("flash.json")) else {
+            return (FlashRecords::default(), None);
+        };
+        let (mut records, problem) = match fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<FlashRecords>(&contents) {
+                Ok(records) => (records, None),
+                Err(err) => (FlashRecords::default(), Some(format!("flash.json: {}", err))),
+            },
+            Err(_) => (FlashRecords::default(), None),
+        };
+        records.path = Some(path);
+        (records, problem)
+    }
+
+    pub fn best_count_down(&self, system: &str) -> Option<u64> {
+        self.best_count_down.get(system).copied()
+    }
+
+    /// Keeps `millis` when it beats the best for `system`, returns whether it did
+    pub fn record_count_down(&mut self, system: &str, millis: u64) -> io::Result<bool> {
+        if self.best_count_down(system).is_some_and(|best| best <= millis) {
+            return Ok(false);
+        }
+        self.best_count_down.insert(system.to_string(), millis);
+        self.save()?;
+        Ok(true)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FlashRecords;
+
+    #[test]
+    fn only_faster_times_become_the_best() {
+        let mut records = FlashRecords::default();
+        assert!(records.record_count_down("Hi-Lo", 30_000).unwrap());
+        assert!(!records.record_count_down("Hi-Lo", 31_000).unwrap());
+        assert!(records.record_count_down("Hi-Lo", 25_000).unwrap());
+        assert_eq!(records.best_count_down("Hi-Lo"), Some(25_000));
+        assert_eq!(records.best_count_down("Zen"), None);
+    }
+}
@@ -0,0 +1,439 @@
+use std::fmt;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::prelude::{Line, Span, Stylize};
+use blackjack_engine::card::Card;
+use crate::drill::flash_records::FlashRecords;
+use crate::event::{AppEvent, TICK_RATE};
+use crate::game::count::CountSystem;
+use crate::game::shoe::{shoe_cards, CARDS_PER_DECK};
+use crate::keybindings::{bindings, Action, Context};
+use crate::menu::menu_widget::{Menu, MenuEvent, MenuItem};
+use crate::model::{Model, ModelResponse};
+use crate::navigation::Navigation;
+use crate::overlay::modal::{Modal, ModalResult};
+use crate::rng::Rng;
+use crate::settings::config::{SettingKey, SharedSettings};
+use crate::theme::{theme, Slot};
+use crate::ui::{card_span, render_border, render_footer_hints, render_line, render_text, FooterHint};
+
+/// Cards flashed before the count is asked for
+const FLASH_CARDS: usize = 20;
+
+/// Cards left out of the deck counted down, so the count doesn't always end at zero
+const HELD_OUT_CARDS: usize = 3;
+
+/// Ticks the table stays empty between two flashes, so a card shown twice in a row still blinks
+const FLASH_GAP_TICKS: u64 = 2;
+
+const COUNT_MODAL: &str = "flash_count";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FlashItem {
+    Flash,
+    CountDown,
+    Interval,
+    CardsAtOnce,
+}
+
+impl fmt::Display for FlashItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            FlashItem::Flash => "Flash cards",
+            FlashItem::CountDown => "Count down a deck",
+            FlashItem::Interval => "Flash every",
+            FlashItem::CardsAtOnce => "Cards at once",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Phase {
+    /// Picking the drill and its speed
+    Setup,
+    /// Cards come and go on their own, `ticks_left` until the next flash
+    Flashing { ticks_left: u64 },
+    /// The player turns the cards over as fast as they can, against the clock
+    CountingDown,
+    /// Waiting for the running count typed into the modal
+    Answering,
+}
+
+/// Cards flashed from a shoe one or two at a time, then the running count is
+/// asked for. Timing comes from counting ticks, which arrive at a steady rate
+/// whatever the keyboard does.
+pub struct FlashScreen {
+    menu: Menu<FlashItem>,
+    settings: SharedSettings,
+    records: FlashRecords,
+    system: CountSystem,
+    rng: Rng,
+    decks: u8,
+    /// Cards left in the shoe, dealt from the back
+    shoe: Vec<Card>,
+    phase: Phase,
+    /// Cards still to come in this run, from the back
+    queue: Vec<Card>,
+    /// The flash on screen right now
+    showing: Vec<Card>,
+    /// Every card shown in this run
+    shown: Vec<Card>,
+    /// Ticks since the count down started
+    ticks: u64,
+    /// Whether this run is the count down
+    counting_down: bool,
+    /// How the last run went
+    result: Option<Line<'static>>,
+}
+
+impl FlashScreen {
+    pub fn new(shared: SharedSettings) -> FlashScreen {
+        let settings = shared.borrow();
+        let (records, problem) = FlashRecords::load(settings.storage_dir());
+        let mut screen = FlashScreen {
+            menu: Menu::new(vec![
+                MenuItem::new(FlashItem::Flash, FlashItem::Flash.to_string())
+                    .hotkey('f')
+                    .description("A card every so often, keep the running count"),
+                MenuItem::new(FlashItem::CountDown, FlashItem::CountDown.to_string())
+                    .hotkey('c')
+                    .description("Turn over a deck as fast as you can, three cards are left out"),
+                MenuItem::new(FlashItem::Interval, "").description("How long each flash stays up"),
+                MenuItem::new(FlashItem::CardsAtOnce, "").description("Single cards, or pairs the way they land on the table"),
+            ]),
+            settings: shared.clone(),
+            records,
+            system: settings.count_system().unwrap_or(CountSystem::HiLo),
+            rng: settings.rng(),
+            decks: settings.decks(),
+            shoe: vec![],
+            phase: Phase::Setup,
+            queue: vec![],
+            showing: vec![],
+            shown: vec![],
+            ticks: 0,
+            counting_down: false,
+            result: problem.map(|problem| Line::from(problem).fg(theme().color(Slot::Loss))),
+        };
+        drop(settings);
+        screen.update_labels();
+        screen
+    }
+
+    fn update_labels(&mut self) {
+        let settings = self.settings.borrow();
+        let interval = format!("{}: < {:.1}s >", FlashItem::Interval, settings.flash_interval_ms() as f64 / 1000.0);
+        let cards = format!("{}: < {} >", FlashItem::CardsAtOnce, settings.flash_cards());
+        drop(settings);
+        for item in self.menu.items_mut() {
+            match item.value {
+                FlashItem::Interval => item.set_label(interval.clone()),
+                FlashItem::CardsAtOnce => item.set_label(cards.clone()),
+                _ => {}
+            }
+        }
+    }
+
+    /// Ticks each flash stays up, rounded up to whole ticks
+    fn interval_ticks(&self) -> u64 {
+        self.settings.borrow().flash_interval_ms().div_ceil(TICK_RATE.as_millis() as u64).max(1)
+    }
+
+    fn start_flashing(&mut self) -> ModelResponse {
+        if self.shoe.len() < FLASH_CARDS {
+            self.shoe = shoe_cards(self.decks);
+            self.rng.shuffle(&mut self.shoe);
+        }
+        self.queue = self.shoe.split_off(self.shoe.len() - FLASH_CARDS);
+        self.start(false);
+        self.phase = Phase::Flashing { ticks_left: self.interval_ticks() };
+        ModelResponse::Refresh
+    }
+
+    fn start_count_down(&mut self) -> ModelResponse {
+        self.queue = shoe_cards(1);
+        self.rng.shuffle(&mut self.queue);
+        self.queue.truncate(CARDS_PER_DECK - HELD_OUT_CARDS);
+        self.start(true);
+        self.phase = Phase::CountingDown;
+        ModelResponse::Refresh
+    }
+
+    fn start(&mut self, counting_down: bool) {
+        self.counting_down = counting_down;
+        self.shown.clear();
+        self.ticks = 0;
+        self.result = None;
+        self.next_flash();
+    }
+
+    /// Puts up the next card or pair, returns false once the run is out of cards
+    fn next_flash(&mut self) -> bool {
+        let count = self.settings.borrow().flash_cards().min(self.queue.len());
+        self.showing = self.queue.split_off(self.queue.len() - count);
+        self.shown.extend(self.showing.iter().cloned());
+        count > 0
+    }
+
+    fn ask_count(&mut self, retry: bool) -> ModelResponse {
+        self.phase = Phase::Answering;
+        self.showing.clear();
+        let question = format!("What is the {} running count?", self.system);
+        let message = if retry { format!("That isn't a number. {}", question) } else { question };
+        ModelResponse::OpenModal(Modal::text_input(COUNT_MODAL, "Count", &message, ""))
+    }
+
+    fn grade(&mut self, answer: i32) -> ModelResponse {
+        self.phase = Phase::Setup;
+        let count = self.system.count(&self.shown);
+        if answer != count {
+            let line = format!("The count was {:+}, not {:+}", count, answer);
+            self.result = Some(Line::from(line).fg(theme().color(Slot::Loss)));
+            return ModelResponse::Refresh;
+        }
+        if !self.counting_down {
+            let line = format!("Right, {:+} after {} cards", count, self.shown.len());
+            self.result = Some(Line::from(line).fg(theme().color(Slot::Win)));
+            return ModelResponse::Refresh;
+        }
+        let millis = self.ticks * TICK_RATE.as_millis() as u64;
+        let best = self.records.best_count_down(&self.system.to_string());
+        let (line, saved) = match self.records.record_count_down(&self.system.to_string(), millis) {
+            Ok(true) => (format!("Right in {}, a new personal best", seconds(millis)), Ok(())),
+            Ok(false) => (format!("Right in {}, the best is {}", seconds(millis), best.map(seconds).unwrap_or_default()), Ok(())),
+            Err(err) => (format!("Right in {}", seconds(millis)), Err(err)),
+        };
+        self.result = Some(Line::from(line).fg(theme().color(Slot::Win)));
+        match saved {
+            Ok(()) => ModelResponse::Refresh,
+            Err(err) => ModelResponse::Toast(format!("Personal best not saved: {}", err)),
+        }
+    }
+
+    fn saved(&mut self, saved: std::io::Result<()>) -> ModelResponse {
+        self.update_labels();
+        match saved {
+            Ok(()) => ModelResponse::Refresh,
+            Err(err) => ModelResponse::Toast(format!("Settings not saved: {}", err)),
+        }
+    }
+
+    /// The interval and cards at once are settings, changed here as well as on the settings screen
+    fn step_selected(&mut self, direction: i8) -> ModelResponse {
+        let key = match self.menu.selected() {
+            Some(FlashItem::Interval) => SettingKey::FlashInterval,
+            Some(FlashItem::CardsAtOnce) => SettingKey::FlashCards,
+            _ => return ModelResponse::NoOp,
+        };
+        let mut settings = self.settings.borrow_mut();
+        let value = key.definition().kind.step(settings.value(key), direction);
+        let saved = settings.set(key, value);
+        drop(settings);
+        self.saved(saved)
+    }
+
+    fn activate(&mut self, item: FlashItem) -> ModelResponse {
+        match item {
+            FlashItem::Flash => self.start_flashing(),
+            FlashItem::CountDown => self.start_count_down(),
+            FlashItem::Interval => self.step_selected(1),
+            // Flips between single cards and pairs
+            FlashItem::CardsAtOnce => self.step_selected(if self.settings.borrow().flash_cards() == 1 { 1 } else { -1 }),
+        }
+    }
+
+    fn handle_tick(&mut self) -> ModelResponse {
+        match self.phase {
+            Phase::CountingDown => {
+                self.ticks += 1;
+                // The clock only shows tenths
+                match self.ticks % 3 {
+                    0 => ModelResponse::Refresh,
+                    _ => ModelResponse::NoOp,
+                }
+            }
+            Phase::Flashing { ticks_left } => {
+                let ticks_left = ticks_left.saturating_sub(1);
+                self.phase = Phase::Flashing { ticks_left };
+                if ticks_left == FLASH_GAP_TICKS && self.interval_ticks() > FLASH_GAP_TICKS * 2 {
+                    self.showing.clear();
+                    return ModelResponse::Refresh;
+                }
+                if ticks_left > 0 {
+                    return ModelResponse::NoOp;
+                }
+                if !self.next_flash() {
+                    return self.ask_count(false);
+                }
+                self.phase = Phase::Flashing { ticks_left: self.interval_ticks() };
+                ModelResponse::Refresh
+            }
+            Phase::Setup | Phase::Answering => ModelResponse::NoOp,
+        }
+    }
+
+    fn footer_hints(&self) -> Vec<FooterHint> {
+        match self.phase {
+            Phase::Setup | Phase::Answering => vec![
+                FooterHint::action(Action::Select),
+                FooterHint::action(Action::Left),
+                FooterHint::action(Action::Right),
+                FooterHint::action(Action::Back),
+                FooterHint::action(Action::Help),
+            ],
+            Phase::CountingDown => vec![
+                FooterHint::new(bindings().key_hint(Action::Select), "Next card"),
+                FooterHint::new(bindings().key_hint(Action::Back), "Stop"),
+            ],
+            Phase::Flashing { .. } => vec![FooterHint::new(bindings().key_hint(Action::Back), "Stop")],
+        }
+    }
+
+    fn best_line(&self) -> String {
+        match self.records.best_count_down(&self.system.to_string()) {
+            Some(best) => format!("Best count down with {}: {}", self.system, seconds(best)),
+            None => format!("No count down with {} yet", self.system),
+        }
+    }
+
+    fn render_cards(&self, frame: &mut Frame, layout: &[Rect]) {
+        let mut spans = vec![];
+        for card in &self.showing {
+            spans.push(card_span(card));
+            spans.push(Span::raw("  "));
+        }
+        render_line(frame, layout[1], Line::from(spans).centered());
+        let status = match self.phase {
+            Phase::CountingDown => format!("{} left  {}", self.queue.len(), seconds(self.ticks * TICK_RATE.as_millis() as u64)),
+            _ => format!("{} of {} cards", self.shown.len(), self.shown.len() + self.queue.len()),
+        };
+        render_text(frame, layout[3], &status);
+    }
+}
+
+/// "24.3s"
+fn seconds(millis: u64) -> String {
+    format!("{:.1}s", millis as f64 / 1000.0)
+}
+
+impl Model for FlashScreen {
+    fn update(&mut self, event: &AppEvent) -> std::io::Result<ModelResponse> {
+        if let AppEvent::ModalClosed { id: COUNT_MODAL, result } = event {
+            let ModalResult::Text(answer) = result else {
+                // Gave up on the count, nothing is graded
+                self.phase = Phase::Setup;
+                return Ok(ModelResponse::Refresh);
+            };
+            return match answer.trim().trim_start_matches('+').parse::<i32>() {
+                Ok(answer) => Ok(self.grade(answer)),
+                Err(_) => Ok(self.ask_count(true)),
+            };
+        }
+        if *event == AppEvent::Tick {
+            return Ok(self.handle_tick());
+        }
+        if self.phase == Phase::Setup {
+            match self.menu.handle(event) {
+                MenuEvent::Activated(item) | MenuEvent::Clicked(item, _) => return Ok(self.activate(item)),
+                MenuEvent::Moved => return Ok(ModelResponse::Refresh),
+                MenuEvent::Disabled(_) => return Ok(ModelResponse::NoOp),
+                MenuEvent::Ignored => {}
+            }
+        }
+        let AppEvent::Key(key) = event else {
+            return Ok(ModelResponse::NoOp);
+        };
+        match (&self.phase, bindings().action(Context::Menu, key)) {
+            (Phase::CountingDown, Some(Action::Select)) => match self.next_flash() {
+                true => Ok(ModelResponse::Refresh),
+                false => Ok(self.ask_count(false)),
+            },
+            // Stops the run without asking for the count
+            (Phase::CountingDown | Phase::Flashing { .. }, Some(Action::Back)) => {
+                self.phase = Phase::Setup;
+                self.showing.clear();
+                Ok(ModelResponse::Refresh)
+            }
+            (Phase::Setup, Some(Action::Right)) => Ok(self.step_selected(1)),
+            (Phase::Setup, Some(Action::Left)) => Ok(self.step_selected(-1)),
+            (_, Some(Action::Quit)) => Ok(ModelResponse::Exit),
+            (_, Some(Action::MainMenu)) => Ok(ModelResponse::Navigate(Navigation::PopToRoot)),
+            (_, Some(Action::Back)) => Ok(ModelResponse::Navigate(Navigation::Pop)),
+            _ => Ok(ModelResponse::NoOp),
+        }
+    }
+
+    fn ui(&mut self, frame: &mut Frame) {
+        let screen = frame.area();
+        render_border(frame, screen);
+        render_text(frame, screen, " Count Flash ");
+
+        let inner = Rect::new(screen.x + 1, screen.y + 1, screen.width.saturating_sub(2), screen.height.saturating_sub(2));
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Fill(1),
+                Constraint::Length(1),  // Cards, or the menu's first line
+                Constraint::Length(1),
+                Constraint::Length(1),  // Progress
+                Constraint::Fill(1),
+                Constraint::Length(1),  // Result
+                Constraint::Length(1),  // Personal best
+                Constraint::Length(1),
+                Constraint::Length(1),  // Footer
+            ])
+            .split(inner);
+
+        if self.phase == Phase::Setup || self.phase == Phase::Answering {
+            let menu_rect = Rect { height: layout[4].bottom() - layout[1].y, ..layout[1] };
+            self.menu.render(frame, menu_rect);
+        } else {
+            self.render_cards(frame, &layout);
+        }
+        if let Some(result) = &self.result {
+            render_line(frame, layout[5], result.clone().centered());
+        }
+        render_text(frame, layout[6], &self.best_line());
+        render_footer_hints(frame, &self.footer_hints(), layout[8]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyCode;
+    use crate::testing::{Harness, ScriptedEvents};
+
+    #[test]
+    fn flashed_cards_are_counted_at_the_end() {
+        let mut harness = Harness::new();
+        // 20 cards a second apart, the seeded shoe counts to -8
+        harness.run(ScriptedEvents::new().type_text("f").key(KeyCode::Enter).ticks(20 * 31));
+        harness.assert_contains("What is the Hi-Lo running count?");
+        harness.run(ScriptedEvents::new().type_text("f").key(KeyCode::Enter).ticks(20 * 31).type_text("-8").key(KeyCode::Enter));
+        harness.assert_contains("Right, -8 after 20 cards");
+    }
+
+    #[test]
+    fn counting_down_a_deck_is_timed() {
+        let mut harness = Harness::new();
+        let mut events = ScriptedEvents::new().type_text("f").key(KeyCode::Down).key(KeyCode::Enter);
+        for _ in 0..49 {
+            events = events.ticks(3).key(KeyCode::Enter);
+        }
+        // The three cards left out of the seeded deck count to +0
+        harness.run(events.type_text("0").key(KeyCode::Enter));
+        harness.assert_contains("a new personal best");
+    }
+
+    #[test]
+    fn drill_options_are_kept_with_the_settings() {
+        let mut harness = Harness::new();
+        harness.run(ScriptedEvents::new().type_text("f").type_text("jj").key(KeyCode::Right).type_text("j").key(KeyCode::Enter));
+        harness.assert_contains("Flash every: < 1.1s >");
+        harness.assert_contains("Cards at once: < 2 >");
+        harness.run(ScriptedEvents::new().type_text("s").type_text("jjjjjjjjjjjjj"));
+        harness.assert_contains("Flash Interval (ms): < 1100 >");
+        harness.assert_contains("Flash Cards At Once: < 2 >");
+    }
+}
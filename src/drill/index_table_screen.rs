@@ -0,0 +1,153 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use crate::event::AppEvent;
+use crate::game::deviations::{DeviationTable, STANDARD_DEVIATIONS};
+use crate::keybindings::{bindings, Action, Context};
+use crate::menu::menu_widget::{Menu, MenuEvent, MenuItem};
+use crate::model::{Model, ModelResponse};
+use crate::navigation::Navigation;
+use crate::ui::{render_border, render_footer_hints, render_text, FooterHint};
+
+/// The deviation table, shared between the practice screen and the index table
+pub type SharedDeviations = Rc<RefCell<DeviationTable>>;
+
+/// Every deviation with its index, changed the same way as a setting
+pub struct IndexTableScreen {
+    menu: Menu<usize>,
+    table: SharedDeviations,
+}
+
+impl IndexTableScreen {
+    pub fn new(table: SharedDeviations) -> IndexTableScreen {
+        let items = STANDARD_DEVIATIONS
+            .iter()
+            .enumerate()
+            .map(|(at, deviation)| {
+                let standard = format!("{}, standard index {:+}", deviation.set, deviation.index);
+                MenuItem::new(at, "").description(standard)
+            })
+            .collect();
+        let mut screen = IndexTableScreen { menu: Menu::new(items), table };
+        screen.update_labels();
+        screen
+    }
+
+    /// "16 vs 10: Stand at < +0 > or higher"
+    fn update_labels(&mut self) {
+        let entries = self.table.borrow().entries();
+        for item in self.menu.items_mut() {
+            let deviation = entries[item.value];
+            let rule = deviation.rule().replacen(&format!("{:+}", deviation.index), &format!("< {:+} >", deviation.index), 1);
+            item.set_label(format!("{}: {}", deviation.name(), rule));
+        }
+    }
+
+    fn saved(&mut self, saved: std::io::Result<()>) -> ModelResponse {
+        self.update_labels();
+        match saved {
+            Ok(()) => ModelResponse::Refresh,
+            Err(err) => ModelResponse::Toast(format!("Index table not saved: {}", err)),
+        }
+    }
+
+    fn step_selected(&mut self, step: i32) -> ModelResponse {
+        let Some(at) = self.menu.selected() else {
+            return ModelResponse::NoOp;
+        };
+        let saved = self.table.borrow_mut().step_index(at, step);
+        self.saved(saved)
+    }
+
+    fn footer_hints(&self) -> Vec<FooterHint> {
+        vec![
+            FooterHint::action(Action::Left),
+            FooterHint::action(Action::Right),
+            FooterHint::action(Action::ResetSetting),
+            FooterHint::action(Action::Back),
+            FooterHint::action(Action::Help),
+        ]
+    }
+}
+
+impl Model for IndexTableScreen {
+    fn update(&mut self, event: &AppEvent) -> std::io::Result<ModelResponse> {
+        match self.menu.handle(event) {
+            // Clicking one of the arrows changes the index
+            MenuEvent::Clicked(_, Some('<')) => return Ok(self.step_selected(-1)),
+            MenuEvent::Clicked(_, Some('>')) => return Ok(self.step_selected(1)),
+            MenuEvent::Moved | MenuEvent::Clicked(..) | MenuEvent::Activated(_) => return Ok(ModelResponse::Refresh),
+            MenuEvent::Disabled(_) => return Ok(ModelResponse::NoOp),
+            MenuEvent::Ignored => {}
+        }
+        let AppEvent::Key(key) = event else {
+            return Ok(ModelResponse::NoOp);
+        };
+        match bindings().action(Context::Menu, key) {
+            Some(Action::Quit) => Ok(ModelResponse::Exit),
+            Some(Action::MainMenu) => Ok(ModelResponse::Navigate(Navigation::PopToRoot)),
+            Some(Action::Back) => Ok(ModelResponse::Navigate(Navigation::Pop)),
+            Some(Action::Right) => Ok(self.step_selected(1)),
+            Some(Action::Left) => Ok(self.step_selected(-1)),
+            Some(Action::ResetSetting) => {
+                let Some(at) = self.menu.selected() else {
+                    return Ok(ModelResponse::NoOp);
+                };
+                let saved = self.table.borrow_mut().reset(at);
+                Ok(self.saved(saved))
+            }
+            _ => Ok(ModelResponse::NoOp),
+        }
+    }
+
+    fn ui(&mut self, frame: &mut Frame) {
+        let screen = frame.area();
+        render_border(frame, screen);
+        render_text(frame, screen, " Index Table ");
+
+        let inner = Rect::new(screen.x + 1, screen.y + 1, screen.width.saturating_sub(2), screen.height.saturating_sub(2));
+        let layout = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Fill(1),    // Entries
+                Constraint::Length(1),
+                Constraint::Length(1),  // Description
+                Constraint::Length(1),
+                Constraint::Length(1),  // Footer
+            ])
+            .split(inner);
+
+        self.menu.render(frame, layout[1]);
+        self.menu.render_description(frame, layout[3]);
+        render_footer_hints(frame, &self.footer_hints(), layout[5]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyCode;
+    use crate::testing::{Harness, ScriptedEvents};
+
+    fn open_index_table() -> ScriptedEvents {
+        ScriptedEvents::new().type_text("vi")
+    }
+
+    #[test]
+    fn indices_can_be_changed_and_reset() {
+        let mut harness = Harness::with_size(80, 24);
+        harness.run(open_index_table().key(KeyCode::Right).key(KeyCode::Right));
+        harness.assert_contains("16 vs 10: Stand at < +2 > or higher");
+        harness.run(open_index_table().type_text("r"));
+        harness.assert_contains("16 vs 10: Stand at < +0 > or higher");
+    }
+
+    #[test]
+    fn the_list_scrolls_to_the_last_entry() {
+        let mut harness = Harness::with_size(80, 24);
+        harness.run(open_index_table().key(KeyCode::Up));
+        harness.assert_contains("15 vs A: Surrender at < +1 > or higher");
+        harness.assert_not_contains("16 vs 10:");
+    }
+}
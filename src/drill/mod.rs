@@ -0,0 +1,6 @@
+pub mod deviation_screen;
+pub mod drill_screen;
+pub mod flash_records;
+pub mod flash_screen;
+pub mod index_table_screen;
+pub mod schedule;
@@ -0,0 +1,251 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use blackjack_engine::card::{Card, Rank, Suit};
+use serde::{Deserialize, Serialize};
+use crate::game::strategy::{HandCategory, Situation};
+use crate::rng::Rng;
+
+/// Answers until a card comes back, by how well it is known. A wrong answer
+/// drops a card to the first level, a quick right one moves it up a level.
+const INTERVALS: [u64; 5] = [2, 6, 15, 40, 100];
+
+/// Right answers slower than this keep their level instead of moving up
+pub const SLOW_ANSWER_MS: u64 = 3000;
+
+/// One player hand against one dealer up card
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Flashcard {
+    pub category: HandCategory,
+    /// Hand total, or the value of one card for pairs
+    pub total: u8,
+    /// 2 to 11, an ace is 11
+    pub dealer_up: u8,
+}
+
+impl Flashcard {
+    /// Every hand basic strategy has a rule for, against every up card
+    pub fn all() -> Vec<Flashcard> {
+        let hands = (5..=19)
+            .map(|total| (HandCategory::Hard, total))
+            .chain((13..=20).map(|total| (HandCategory::Soft, total)))
+            .chain((2..=11).map(|value| (HandCategory::Pair, value)));
+        hands
+            .flat_map(|(category, total)| (2..=11).map(move |dealer_up| Flashcard { category, total, dealer_up }))
+            .collect()
+    }
+
+    pub fn situation(&self) -> Situation {
+        Situation {
+            category: self.category,
+            total: self.total,
+            dealer_up: self.dealer_up,
+            can_double: true,
+            can_split: true,
+            can_surrender: false,
+        }
+    }
+
+    /// Two cards making up the hand and the dealer's up card, in random suits
+    pub fn deal(&self, rng: &mut Rng) -> (Vec<Card>, Card) {
+        let (first, second) = match self.category {
+            HandCategory::Pair => (self.total, self.total),
+            HandCategory::Soft => (11, self.total - 11),
+            // Two different cards, neither of them an ace
+            HandCategory::Hard if self.total <= 11 => (2, self.total - 2),
+            HandCategory::Hard => (10, self.total - 10),
+        };
+        let mut card = |value| Card { rank: rank_for(value, rng), suit: *rng.pick(&SUITS) };
+        let mut hand = vec![card(first), card(second)];
+        // Only cards of the same rank can be split
+        if self.category == HandCategory::Pair {
+            hand[1].rank = hand[0].rank;
+        }
+        (hand, card(self.dealer_up))
+    }
+}
+
+const SUITS: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+/// A rank worth `value`, any of the four for ten
+fn rank_for(value: u8, rng: &mut Rng) -> Rank {
+    match value {
+        2 => Rank::Two,
+        3 => Rank::Three,
+        4 => Rank::Four,
+        5 => Rank::Five,
+        6 => Rank::Six,
+        7 => Rank::Seven,
+        8 => Rank::Eight,
+        9 => Rank::Nine,
+        10 => *rng.pick(&[Rank::Ten, Rank::Jack, Rank::Queen, Rank::King]),
+        _ => Rank::Ace,
+    }
+}
+
+/// How well one flashcard is known, and when it is due again
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Review {
+    card: Flashcard,
+    level: usize,
+    /// Due once this many answers were given in total
+    due: u64,
+}
+
+/// Answers given in one sitting
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DrillSession {
+    /// Seconds since the Unix epoch
+    pub started: u64,
+    pub answers: u32,
+    pub correct: u32,
+    pub total_ms: u64,
+}
+
+impl DrillSession {
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.answers > 0).then(|| self.correct as f64 * 100.0 / self.answers as f64)
+    }
+
+    pub fn average_ms(&self) -> Option<u64> {
+        (self.answers > 0).then(|| self.total_ms / self.answers as u64)
+    }
+}
+
+/// The spaced repetition schedule and past sessions, saved to `drill.json`
+/// after every answer
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct DrillProgress {
+    /// Answers given across all sessions, the clock the schedule runs on
+    answered: u64,
+    reviews: Vec<Review>,
+    sessions: Vec<DrillSession>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl DrillProgress {
+    /// Reads the progress in `dir`, or starts afresh. Without a directory nothing is saved.
+    pub fn load(dir: Option<PathBuf>) -> (DrillProgress, Option<String>) {
+        let Some(path) = dir.map(|dir| dir.join("drill.json")) else {
+            return (DrillProgress::default(), None);
+        };
+        let (mut progress, problem) = match fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<DrillProgress>(&contents) {
+                Ok(progress) => (progress, None),
+                Err(err) => (DrillProgress::default(), Some(format!("drill.json: {}", err))),
+            },
+            Err(_) => (DrillProgress::default(), None),
+        };
+        progress.path = Some(path);
+        (progress, problem)
+    }
+
+    pub fn start_session(&mut self) {
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0);
+        self.sessions.push(DrillSession { started, ..DrillSession::default() });
+    }
+
+    /// Past sessions, oldest first, ending with the current one
+    pub fn sessions(&self) -> &[DrillSession] {
+        &self.sessions
+    }
+
+    /// Cards due for another look right now
+    pub fn due_count(&self) -> usize {
+        self.reviews.iter().filter(|review| review.due <= self.answered).count()
+    }
+
+    /// The most overdue card, else one never seen before, else the one due soonest
+    pub fn next_card(&self, rng: &mut Rng) -> Flashcard {
+        let overdue = self.reviews.iter().filter(|review| review.due <= self.answered).min_by_key(|review| review.due);
+        if let Some(review) = overdue {
+            return review.card;
+        }
+        let unseen = Flashcard::all()
+            .into_iter()
+            .filter(|card| self.reviews.iter().all(|review| review.card != *card))
+            .collect::<Vec<_>>();
+        if !unseen.is_empty() {
+            return *rng.pick(&unseen);
+        }
+        self.reviews.iter().min_by_key(|review| review.due).map(|review| review.card).unwrap_or(Flashcard::all()[0])
+    }
+
+    /// Schedules `card` again by how it was answered and saves the progress
+    pub fn record(&mut self, card: Flashcard, correct: bool, millis: u64) -> io::Result<()> {
+        self.answered += 1;
+        let index = match self.reviews.iter().position(|review| review.card == card) {
+            Some(index) => index,
+            None => {
+                self.reviews.push(Review { card, level: 0, due: 0 });
+                self.reviews.len() - 1
+            }
+        };
+        let review = &mut self.reviews[index];
+        review.level = match (correct, millis < SLOW_ANSWER_MS) {
+            (false, _) => 0,
+            (true, false) => review.level,
+            (true, true) => (review.level + 1).min(INTERVALS.len() - 1),
+        };
+        review.due = self.answered + INTERVALS[review.level];
+
+        if self.sessions.is_empty() {
+            self.start_session();
+        }
+        if let Some(session) = self.sessions.last_mut() {
+            session.answers += 1;
+            session.correct += correct as u32;
+            session.total_ms += millis;
+        }
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::strategy::{hand_total, HandCategory};
+    use crate::rng::Rng;
+    use super::{DrillProgress, Flashcard, SLOW_ANSWER_MS};
+
+    #[test]
+    fn dealt_cards_match_the_flashcard() {
+        let mut rng = Rng::new(42);
+        for card in Flashcard::all() {
+            let (hand, _) = card.deal(&mut rng);
+            let (total, soft) = hand_total(&hand);
+            match card.category {
+                HandCategory::Pair => assert_eq!(hand[0].rank, hand[1].rank),
+                HandCategory::Soft => assert!(soft && total == card.total, "{:?}", card),
+                HandCategory::Hard => assert!(!soft && total == card.total && hand[0].rank != hand[1].rank, "{:?}", card),
+            }
+        }
+    }
+
+    #[test]
+    fn wrong_and_slow_answers_come_back_sooner() {
+        let mut progress = DrillProgress::default();
+        let mut rng = Rng::new(7);
+        let missed = progress.next_card(&mut rng);
+        progress.record(missed, false, 500).unwrap();
+        let slow = progress.next_card(&mut rng);
+        progress.record(slow, true, SLOW_ANSWER_MS + 1).unwrap();
+
+        // Two answers later the missed card is due again, ahead of new cards
+        let other = progress.next_card(&mut rng);
+        progress.record(other, true, 500).unwrap();
+        assert_eq!(progress.next_card(&mut rng), missed);
+        assert_eq!(progress.sessions()[0].answers, 3);
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt;
+use std::io;
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use crate::error::error_screen::ErrorMenuOption::{Quit, ReturnToMenu};
+use crate::event::AppEvent;
+use crate::keybindings::{bindings, Action, Context};
+use crate::menu::menu_widget::{Menu, MenuEvent, MenuItem};
+use crate::model::{Model, ModelResponse};
+use crate::navigation::Navigation;
+use crate::ui::{render_border, render_text};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ErrorMenuOption {
+    ReturnToMenu,
+    Quit,
+}
+
+impl fmt::Display for ErrorMenuOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            ReturnToMenu => "Return to Menu",
+            Quit => "Quit",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Shown in place of a screen that failed with a recoverable error, so the
+/// session can carry on instead of being torn down.
+pub struct ErrorScreen {
+    details: String,
+    menu: Menu<ErrorMenuOption>,
+}
+
+impl ErrorScreen {
+    pub fn new(error: io::Error) -> ErrorScreen {
+        ErrorScreen {
+            details: error.to_string(),
+            menu: Menu::new(vec![
+                MenuItem::new(ReturnToMenu, ReturnToMenu.to_string()),
+                MenuItem::new(Quit, Quit.to_string()),
+            ]),
+        }
+    }
+
+    fn render_details(&self, frame: &mut Frame, rect: Rect) {
+        let details = Paragraph::new(self.details.as_str())
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(Block::default());
+        frame.render_widget(details, rect);
+    }
+
+    fn return_navigation_target(&self, selected_option: ErrorMenuOption) -> ModelResponse {
+        match selected_option {
+            ReturnToMenu => ModelResponse::Navigate(Navigation::PopToRoot),
+            Quit => ModelResponse::Exit,
+        }
+    }
+}
+
+impl Model for ErrorScreen {
+    fn update(&mut self, event: &AppEvent) -> io::Result<ModelResponse> {
+        match self.menu.handle(event) {
+            MenuEvent::Activated(option) | MenuEvent::Clicked(option, _) => {
+                return Ok(self.return_navigation_target(option));
+            }
+            MenuEvent::Moved => return Ok(ModelResponse::Refresh),
+            MenuEvent::Disabled(_) => return Ok(ModelResponse::NoOp),
+            MenuEvent::Ignored => {}
+        }
+        let AppEvent::Key(key) = event else {
+            return Ok(ModelResponse::NoOp);
+        };
+        match bindings().action(Context::Menu, key) {
+            Some(Action::Quit) => Ok(ModelResponse::Exit),
+            Some(Action::MainMenu) => Ok(ModelResponse::Navigate(Navigation::PopToRoot)),
+            _ => Ok(ModelResponse::NoOp),
+        }
+    }
+
+    fn ui(&mut self, frame: &mut Frame) {
+        let screen = frame.area();
+        render_border(frame, screen);
+
+        let error_layout = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(2)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(1),
+                Constraint::Min(3),
+                Constraint::Length(5),
+            ])
+            .split(screen);
+
+        render_text(frame, error_layout[0], "Something went wrong");
+        render_text(frame, error_layout[1], "The screen you were on hit an error:");
+        self.render_details(frame, error_layout[2]);
+        self.menu.render(frame, error_layout[3]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use crate::event::AppEvent;
+    use crate::model::{Model, ModelResponse};
+    use crate::navigation::Navigation;
+    use super::ErrorScreen;
+
+    fn press(screen: &mut ErrorScreen, code: KeyCode) -> ModelResponse {
+        screen.update(&AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))).unwrap()
+    }
+
+    #[test]
+    fn offers_menu_then_quit() {
+        let mut screen = ErrorScreen::new(io::Error::other("shoe ran dry"));
+        assert!(matches!(press(&mut screen, KeyCode::Enter), ModelResponse::Navigate(Navigation::PopToRoot)));
+        press(&mut screen, KeyCode::Down);
+        assert!(matches!(press(&mut screen, KeyCode::Enter), ModelResponse::Exit));
+    }
+}
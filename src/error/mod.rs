@@ -0,0 +1 @@
+pub mod error_screen;
\ No newline at end of file
@@ -0,0 +1,109 @@
+use std::io;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use ratatui::crossterm::event;
+use ratatui::crossterm::event::{Event, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
+use crate::overlay::modal::ModalResult;
+
+/// Time between two `AppEvent::Tick`s (~30 per second)
+pub const TICK_RATE: Duration = Duration::from_millis(33);
+
+/// Everything a screen can react to. Screens receive these from the main
+/// loop and never talk to the terminal themselves.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AppEvent {
+    /// A key was pressed (releases are filtered out)
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    /// The terminal was resized to the given columns and rows
+    Resize(u16, u16),
+    /// Sent every `TICK_RATE`, drives animations, timers and blinking cursors
+    Tick,
+    /// A modal opened by the current screen was closed
+    ModalClosed { id: &'static str, result: ModalResult },
+}
+
+impl AppEvent {
+    /// Where the left mouse button was pressed, if this is such a press
+    pub fn left_click(&self) -> Option<Position> {
+        match self {
+            AppEvent::Mouse(MouseEvent { kind: MouseEventKind::Down(MouseButton::Left), column, row, .. }) => {
+                Some(Position::new(*column, *row))
+            }
+            _ => None,
+        }
+    }
+
+    /// 1 for the wheel scrolled up, -1 for down
+    pub fn scroll(&self) -> Option<i8> {
+        match self {
+            AppEvent::Mouse(MouseEvent { kind: MouseEventKind::ScrollUp, .. }) => Some(1),
+            AppEvent::Mouse(MouseEvent { kind: MouseEventKind::ScrollDown, .. }) => Some(-1),
+            _ => None,
+        }
+    }
+}
+
+/// Anything that can feed `AppEvent`s to the main loop
+pub trait EventSource {
+    /// Blocks until the next event is available. `None` means the source
+    /// has run dry and the app should shut down.
+    fn next(&mut self) -> io::Result<Option<AppEvent>>;
+}
+
+/// Merges terminal input and a steady tick into a single stream of `AppEvent`s.
+///
+/// Input and ticks are produced on their own threads, so waiting for a key
+/// press never holds up a tick and vice versa.
+pub struct EventHandler {
+    receiver: mpsc::Receiver<io::Result<AppEvent>>,
+}
+
+impl EventHandler {
+    pub fn new(tick_rate: Duration) -> EventHandler {
+        let (sender, receiver) = mpsc::channel();
+
+        let input_sender = sender.clone();
+        thread::spawn(move || loop {
+            let app_event = match event::read() {
+                Ok(Event::Key(key)) if key.kind != KeyEventKind::Release => AppEvent::Key(key),
+                Ok(Event::Mouse(mouse)) => AppEvent::Mouse(mouse),
+                Ok(Event::Resize(columns, rows)) => AppEvent::Resize(columns, rows),
+                Ok(_) => continue,
+                Err(err) => {
+                    let _ = input_sender.send(Err(err));
+                    return;
+                }
+            };
+            if input_sender.send(Ok(app_event)).is_err() {
+                return;
+            }
+        });
+
+        // Each tick is scheduled off the first one, so ticks don't drift
+        // and counting them is as good as a clock for timers
+        thread::spawn(move || {
+            let mut next_tick = Instant::now();
+            loop {
+                next_tick += tick_rate;
+                thread::sleep(next_tick.saturating_duration_since(Instant::now()));
+                if sender.send(Ok(AppEvent::Tick)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        EventHandler { receiver }
+    }
+}
+
+impl EventSource for EventHandler {
+    fn next(&mut self) -> io::Result<Option<AppEvent>> {
+        self.receiver
+            .recv()
+            .map_err(|err| io::Error::new(io::ErrorKind::BrokenPipe, err))?
+            .map(Some)
+    }
+}
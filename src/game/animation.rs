@@ -0,0 +1,180 @@
+use std::collections::VecDeque;
+use clap::ValueEnum;
+use ratatui::layout::Rect;
+
+/// How fast the table animations play. `Off` makes every change appear instantly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum AnimationSpeed {
+    Off,
+    Slow,
+    Normal,
+    Fast,
+}
+
+impl AnimationSpeed {
+    /// Number of frames (ticks) a single animation lasts at this speed
+    pub fn frames(&self) -> u16 {
+        match self {
+            AnimationSpeed::Off => 0,
+            AnimationSpeed::Slow => 24,
+            AnimationSpeed::Normal => 12,
+            AnimationSpeed::Fast => 6,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AnimationSpeed::Off => "Off",
+            AnimationSpeed::Slow => "Slow",
+            AnimationSpeed::Normal => "Normal",
+            AnimationSpeed::Fast => "Fast",
+        }
+    }
+}
+
+/// A spot on the table that cards can be dealt to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Seat {
+    Dealer,
+    /// A player hand, by index
+    Player(usize),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnimationKind {
+    /// A card slides from the shoe to a seat. The dealer's hole card travels face down.
+    Deal { seat: Seat, face_up: bool },
+    /// The dealer turns over the hole card
+    FlipHoleCard,
+    /// Chips slide from the losing side of the table to the winner
+    MoveChips { amount: f64, to_player: bool },
+    /// Nothing moves, used to pace the dealer's turn
+    Pause,
+    /// The dealer shuffles the shoe between two rounds
+    Shuffle,
+    /// The first card after the shuffle slides face down from the shoe to the discard tray
+    BurnCard,
+}
+
+/// How the dealer's hole card should currently be drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HoleCardFace {
+    Down,
+    /// Half way through the flip, the card is seen from its edge
+    Edge,
+    Up,
+}
+
+#[derive(Clone, Debug)]
+pub struct Animation {
+    pub kind: AnimationKind,
+    frame: u16,
+    length: u16,
+}
+
+impl Animation {
+    /// How far along the animation is, from 0.0 to 1.0
+    pub fn progress(&self) -> f64 {
+        if self.length == 0 {
+            return 1.0;
+        }
+        self.frame as f64 / self.length as f64
+    }
+}
+
+/// Queue of animations played one after another, advanced once per `AppEvent::Tick`.
+pub struct Animator {
+    speed: AnimationSpeed,
+    queue: VecDeque<Animation>,
+}
+
+impl Animator {
+    pub fn new(speed: AnimationSpeed) -> Animator {
+        Animator {
+            speed,
+            queue: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, kind: AnimationKind) {
+        if self.speed == AnimationSpeed::Off {
+            return;
+        }
+        self.queue.push_back(Animation {
+            kind,
+            frame: 0,
+            length: self.speed.frames(),
+        });
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Advance the current animation by one frame
+    pub fn tick(&mut self) {
+        if let Some(animation) = self.queue.front_mut() {
+            animation.frame += 1;
+            if animation.frame >= animation.length {
+                self.queue.pop_front();
+            }
+        }
+    }
+
+    /// Drop every queued animation, jumping straight to the end state
+    pub fn skip(&mut self) {
+        self.queue.clear();
+    }
+
+    pub fn current(&self) -> Option<&Animation> {
+        self.queue.front()
+    }
+
+    /// Number of cards headed to `seat` that have not landed yet
+    pub fn pending_deals(&self, seat: Seat) -> usize {
+        self.queue
+            .iter()
+            .filter(|a| matches!(a.kind, AnimationKind::Deal { seat: s, .. } if s == seat))
+            .count()
+    }
+
+    /// Face of the hole card once the dealer has started revealing it
+    pub fn hole_card_face(&self) -> HoleCardFace {
+        let flip = self.queue.iter().position(|a| a.kind == AnimationKind::FlipHoleCard);
+        match flip {
+            None => HoleCardFace::Up,
+            Some(0) => {
+                let progress = self.queue[0].progress();
+                if progress < 0.4 {
+                    HoleCardFace::Down
+                } else if progress < 0.6 {
+                    HoleCardFace::Edge
+                } else {
+                    HoleCardFace::Up
+                }
+            }
+            Some(_) => HoleCardFace::Down,
+        }
+    }
+}
+
+/// Position of a `width` wide, single line sprite moving from `from` to `to`
+pub fn sprite_rect(from: Rect, to: Rect, progress: f64, width: u16) -> Rect {
+    let (from_x, from_y) = centre(from);
+    let (to_x, to_y) = centre(to);
+    let x = from_x + (to_x - from_x) * progress;
+    let y = from_y + (to_y - from_y) * progress;
+    Rect {
+        x: (x - width as f64 / 2.0).max(0.0) as u16,
+        y: y.max(0.0) as u16,
+        width,
+        height: 1,
+    }
+}
+
+fn centre(rect: Rect) -> (f64, f64) {
+    (
+        rect.x as f64 + rect.width as f64 / 2.0,
+        rect.y as f64 + rect.height as f64 / 2.0,
+    )
+}
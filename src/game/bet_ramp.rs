@@ -0,0 +1,91 @@
+use crate::game::trainer::Score;
+
+/// True count from which the whole spread is bet
+pub const TOP_COUNT: i32 = 5;
+
+/// How many units to bet as the count rises: one unit up to a true count of
+/// +1, then evenly more up to the top of the spread at `TOP_COUNT`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BetRamp {
+    /// Dollars in one unit
+    pub unit: f64,
+    /// Units bet at the top of the ramp, 12 for a 1-12 spread
+    pub spread: u32,
+}
+
+impl BetRamp {
+    pub fn units(&self, count: i32) -> u32 {
+        if count <= 1 {
+            return 1;
+        }
+        let per_count = (self.spread.saturating_sub(1)) as f64 / (TOP_COUNT - 1) as f64;
+        let units = 1.0 + per_count * (count - 1) as f64;
+        (units.round() as u32).min(self.spread).max(1)
+    }
+
+    pub fn bet(&self, count: i32) -> f64 {
+        self.units(count) as f64 * self.unit
+    }
+}
+
+/// The count a bet is sized on: the true count rounded down, or the running
+/// count for unbalanced systems, which are built to be bet off it
+pub fn betting_count(running_count: i32, true_count: Option<f64>) -> i32 {
+    true_count.map(|true_count| true_count.floor() as i32).unwrap_or(running_count)
+}
+
+/// How the bets of a session measured up to the ramp
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct BetTally {
+    pub on_ramp: Score,
+    /// Dollars bet above the ramp, and on how many bets
+    pub over: f64,
+    pub over_bets: u32,
+    /// Dollars bet below the ramp, and on how many bets
+    pub under: f64,
+    pub under_bets: u32,
+}
+
+impl BetTally {
+    pub fn add(&mut self, placed: f64, recommended: f64) {
+        self.on_ramp.add(placed == recommended);
+        if placed > recommended {
+            self.over += placed - recommended;
+            self.over_bets += 1;
+        } else if placed < recommended {
+            self.under += recommended - placed;
+            self.under_bets += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{betting_count, BetRamp, BetTally};
+
+    #[test]
+    fn the_ramp_climbs_from_one_unit_to_the_top_of_the_spread() {
+        let ramp = BetRamp { unit: 10.0, spread: 12 };
+        let units = (-2..=7).map(|count| ramp.units(count)).collect::<Vec<_>>();
+        assert_eq!(units, [1, 1, 1, 1, 4, 7, 9, 12, 12, 12]);
+        assert_eq!(ramp.bet(3), 70.0);
+        assert_eq!(BetRamp { unit: 10.0, spread: 1 }.units(6), 1);
+
+        // True counts are rounded down, towards the smaller bet
+        assert_eq!(betting_count(5, Some(2.9)), 2);
+        assert_eq!(betting_count(5, Some(-0.5)), -1);
+        assert_eq!(betting_count(5, None), 5);
+    }
+
+    #[test]
+    fn over_and_under_bets_are_added_up() {
+        let mut tally = BetTally::default();
+        tally.add(10.0, 10.0);
+        tally.add(50.0, 10.0);
+        tally.add(25.0, 10.0);
+        tally.add(40.0, 70.0);
+        assert_eq!(tally.on_ramp.to_string(), "1/4 (25%)");
+        assert_eq!((tally.over, tally.over_bets), (55.0, 2));
+        assert_eq!((tally.under, tally.under_bets), (30.0, 1));
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+use blackjack_engine::card::{Card, Rank};
+use clap::ValueEnum;
+
+/// Card counting systems the count panel can keep
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CountSystem {
+    HiLo,
+    /// Knock-Out, unbalanced so there is no true count
+    Ko,
+    OmegaII,
+    Zen,
+}
+
+impl fmt::Display for CountSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            CountSystem::HiLo => "Hi-Lo",
+            CountSystem::Ko => "KO",
+            CountSystem::OmegaII => "Omega II",
+            CountSystem::Zen => "Zen",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+impl CountSystem {
+    /// Same order as the options of the count system setting, after "Off"
+    pub const ALL: [CountSystem; 4] = [CountSystem::HiLo, CountSystem::Ko, CountSystem::OmegaII, CountSystem::Zen];
+
+    /// What seeing `card` adds to the running count
+    pub fn tag(&self, card: &Card) -> i32 {
+        use Rank::*;
+        match (self, card.rank) {
+            (CountSystem::HiLo, Two | Three | Four | Five | Six) => 1,
+            (CountSystem::HiLo, Seven | Eight | Nine) => 0,
+            (CountSystem::HiLo, _) => -1,
+            (CountSystem::Ko, Two | Three | Four | Five | Six | Seven) => 1,
+            (CountSystem::Ko, Eight | Nine) => 0,
+            (CountSystem::Ko, _) => -1,
+            (CountSystem::OmegaII | CountSystem::Zen, Two | Three | Seven) => 1,
+            (CountSystem::OmegaII | CountSystem::Zen, Four | Five | Six) => 2,
+            (CountSystem::OmegaII | CountSystem::Zen, Eight) => 0,
+            (CountSystem::OmegaII, Nine) => -1,
+            (CountSystem::OmegaII, Ace) => 0,
+            (CountSystem::Zen, Nine) => 0,
+            (CountSystem::Zen, Ace) => -1,
+            (CountSystem::OmegaII | CountSystem::Zen, _) => -2,
+        }
+    }
+
+    /// Balanced systems add up to zero over a full shoe and convert to a true count
+    pub fn is_balanced(&self) -> bool {
+        *self != CountSystem::Ko
+    }
+
+    /// Where the count starts off a fresh shoe. KO starts below zero so that
+    /// it lands on its key count instead of needing a true count.
+    pub fn initial_count(&self, decks: u8) -> i32 {
+        match self {
+            CountSystem::Ko => 4 - 4 * decks as i32,
+            _ => 0,
+        }
+    }
+
+    /// The tags of `cards` added up
+    pub fn count(&self, cards: &[Card]) -> i32 {
+        cards.iter().map(|card| self.tag(card)).sum()
+    }
+
+    pub fn running_count(&self, decks: u8, seen: &[Card]) -> i32 {
+        self.initial_count(decks) + self.count(seen)
+    }
+
+    /// Running count per deck left to deal, `None` for unbalanced systems
+    pub fn true_count(&self, running_count: i32, decks_remaining: f64) -> Option<f64> {
+        self.is_balanced().then(|| running_count as f64 / decks_remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::shoe::shoe_cards;
+    use super::CountSystem;
+
+    #[test]
+    fn balanced_systems_end_a_deck_at_zero() {
+        for system in CountSystem::ALL {
+            let count = system.running_count(1, &shoe_cards(1));
+            assert_eq!(count == 0, system.is_balanced(), "{} ended at {}", system, count);
+        }
+        // KO ends on +4 per deck above where it started
+        assert_eq!(CountSystem::Ko.running_count(6, &shoe_cards(1)), -20 + 4);
+    }
+
+    #[test]
+    fn true_count_is_per_deck_remaining() {
+        assert_eq!(CountSystem::HiLo.true_count(6, 2.0), Some(3.0));
+        assert_eq!(CountSystem::Ko.true_count(6, 2.0), None);
+    }
+}
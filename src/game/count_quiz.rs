@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ratatui::prelude::{Line, Stylize};
+use serde::{Deserialize, Serialize};
+use crate::game::animation::AnimationSpeed;
+use crate::game::count::CountSystem;
+use crate::game::trainer::Score;
+use crate::overlay::help::HelpOverlay;
+use crate::rng::Rng;
+
+/// One round in this many ends with a count check, on top of the one before every reshuffle
+const QUIZ_ODDS: usize = 3;
+
+/// A true count this close to the exact one is right, the decks left are only ever estimated
+const TRUE_COUNT_TOLERANCE: f64 = 0.5;
+
+/// Running counts looked at to tell whether the dealer can speed up
+const RECENT_ANSWERS: usize = 10;
+const FASTER_ACCURACY: f64 = 90.0;
+
+/// Past sessions listed in the report
+const REPORTED_SESSIONS: usize = 8;
+
+/// Count checks answered in one sitting
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct QuizSession {
+    /// Seconds since the Unix epoch
+    pub started: u64,
+    pub system: String,
+    /// Dealer speed the session was played at
+    pub speed: String,
+    pub running: Score,
+    pub true_count: Score,
+}
+
+/// Every count practice session, saved to `count_quiz.json` after every answer
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct QuizProgress {
+    sessions: Vec<QuizSession>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl QuizProgress {
+    /// Reads the progress in `dir`, or starts afresh. Without a directory nothing is saved.
+    pub fn load(dir: Option<PathBuf>) -> (QuizProgress, Option<String>) {
+        let Some(path) = dir.map(|dir| dir.join("count_quiz.json")) else {
+            return (QuizProgress::default(), None);
+        };
+        let (mut progress, problem) = match fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<QuizProgress>(&contents) {
+                Ok(progress) => (progress, None),
+                Err(err) => (QuizProgress::default(), Some(format!("count_quiz.json: {}", err))),
+            },
+            Err(_) => (QuizProgress::default(), None),
+        };
+        progress.path = Some(path);
+        (progress, problem)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)
+    }
+}
+
+/// Counting practice: the count stays hidden and the player is asked for it
+/// at the end of some rounds, and always before the shoe is reshuffled.
+#[derive(Clone, Debug)]
+pub struct CountQuiz {
+    system: CountSystem,
+    progress: QuizProgress,
+    rng: Rng,
+    /// Whether the latest running counts were right, most recent last
+    recent: VecDeque<bool>,
+    /// Trouble reading the saved progress, shown once
+    problem: Option<String>,
+}
+
+impl CountQuiz {
+    pub fn new(system: CountSystem, speed: AnimationSpeed, dir: Option<PathBuf>) -> CountQuiz {
+        let (mut progress, problem) = QuizProgress::load(dir);
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0);
+        progress.sessions.push(QuizSession {
+            started,
+            system: system.to_string(),
+            speed: speed.label().to_string(),
+            ..QuizSession::default()
+        });
+        CountQuiz { system, progress, rng: Rng::from_time(), recent: VecDeque::new(), problem }
+    }
+
+    pub fn system(&self) -> CountSystem {
+        self.system
+    }
+
+    pub fn take_problem(&mut self) -> Option<String> {
+        self.problem.take()
+    }
+
+    /// Whether to ask for the count now the round is over
+    pub fn should_ask(&mut self, reshuffle_next: bool) -> bool {
+        reshuffle_next || self.rng.below(QUIZ_ODDS) == 0
+    }
+
+    /// The session being played
+    pub fn session(&self) -> &QuizSession {
+        // `new` starts a session, there always is one
+        &self.progress.sessions[self.progress.sessions.len() - 1]
+    }
+
+    /// Grades the answers against the actual counts and saves the progress.
+    /// The true count is only asked for with balanced systems. Returns a line
+    /// of feedback for the player.
+    pub fn answer(
+        &mut self,
+        running: i32,
+        actual_running: i32,
+        true_count: Option<(f64, f64)>,
+    ) -> io::Result<String> {
+        let running_right = running == actual_running;
+        let session = self.progress.sessions.last_mut().expect("a session was started");
+        session.running.add(running_right);
+        let mut feedback = match running_right {
+            true => format!("Running count {:+} is right", actual_running),
+            false => format!("Running count was {:+}, not {:+}", actual_running, running),
+        };
+        if let Some((answer, actual)) = true_count {
+            let true_right = (answer - actual).abs() <= TRUE_COUNT_TOLERANCE;
+            session.true_count.add(true_right);
+            feedback += &match true_right {
+                true => format!(", true count {:+.1} too", actual),
+                false => format!(", true count was {:+.1}", actual),
+            };
+        }
+
+        self.recent.push_back(running_right);
+        if self.recent.len() > RECENT_ANSWERS {
+            self.recent.pop_front();
+        }
+        if self.ready_for_faster() {
+            feedback += ". Try a faster dealer";
+        }
+        self.progress.save()?;
+        Ok(feedback)
+    }
+
+    /// Nearly every recent running count was right at a speed that can still go up
+    fn ready_for_faster(&self) -> bool {
+        let recent = Score {
+            correct: self.recent.iter().filter(|right| **right).count() as u32,
+            total: self.recent.len() as u32,
+        };
+        let accurate = recent.total as usize == RECENT_ANSWERS && recent.accuracy().unwrap_or(0.0) >= FASTER_ACCURACY;
+        accurate && self.session().speed != AnimationSpeed::Fast.label()
+    }
+
+    /// Accuracy of this session and the ones before it
+    pub fn report(&self) -> HelpOverlay {
+        let sessions = &self.progress.sessions;
+        let mut lines = vec![Line::from("Sessions").bold().underlined()];
+        let skip = sessions.len().saturating_sub(REPORTED_SESSIONS);
+        for (i, session) in sessions.iter().enumerate().skip(skip).rev() {
+            let label = if i + 1 == sessions.len() { "This session".to_string() } else { format!("Session {}", i + 1) };
+            lines.push(Line::from(format!("  {}, {} at {} speed", label, session.system, session.speed)));
+            lines.push(Line::from(format!("    Running count: {}", session.running)));
+            if session.true_count.total > 0 {
+                lines.push(Line::from(format!("    True count: {}", session.true_count)));
+            }
+        }
+        let overall = sessions.iter().fold(Score::default(), |sum, session| Score {
+            correct: sum.correct + session.running.correct,
+            total: sum.total + session.running.total,
+        });
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("All time running count: {}", overall)).bold());
+        HelpOverlay::page("Count report", lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::animation::AnimationSpeed;
+    use crate::game::count::CountSystem;
+    use super::CountQuiz;
+
+    #[test]
+    fn answers_are_graded_and_scored() {
+        let mut quiz = CountQuiz::new(CountSystem::HiLo, AnimationSpeed::Slow, None);
+        assert_eq!(quiz.answer(3, 3, Some((1.0, 1.4))).unwrap(), "Running count +3 is right, true count +1.4 too");
+        assert_eq!(quiz.answer(2, -1, Some((0.0, -0.6))).unwrap(), "Running count was -1, not +2, true count was -0.6");
+        assert_eq!(quiz.session().running.to_string(), "1/2 (50%)");
+        assert_eq!(quiz.session().true_count.to_string(), "1/2 (50%)");
+        assert!(quiz.should_ask(true));
+    }
+
+    #[test]
+    fn a_run_of_right_answers_suggests_a_faster_dealer() {
+        let mut quiz = CountQuiz::new(CountSystem::Ko, AnimationSpeed::Normal, None);
+        let feedback = (0..10).map(|_| quiz.answer(0, 0, None).unwrap()).last().unwrap();
+        assert!(feedback.ends_with("Try a faster dealer"), "{}", feedback);
+
+        let mut fast = CountQuiz::new(CountSystem::Ko, AnimationSpeed::Fast, None);
+        let feedback = (0..10).map(|_| fast.answer(0, 0, None).unwrap()).last().unwrap();
+        assert!(!feedback.contains("faster"));
+    }
+}
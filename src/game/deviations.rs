@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use crate::game::rules::HouseRules;
+use crate::game::strategy::{best_play, HandCategory, Play, Situation};
+
+/// The two well known lists of Hi-Lo index plays
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DeviationSet {
+    /// The plays worth the most. Insurance is left out, the table doesn't offer it.
+    Illustrious18,
+    /// Surrenders, for tables that allow it
+    Fab4,
+}
+
+impl fmt::Display for DeviationSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            DeviationSet::Illustrious18 => "Illustrious 18",
+            DeviationSet::Fab4 => "Fab 4",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// A hand played differently from basic strategy once the true count reaches an index
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Deviation {
+    pub set: DeviationSet,
+    pub category: HandCategory,
+    /// Hand total, or the value of one card for pairs
+    pub total: u8,
+    /// 2 to 11, an ace is 11
+    pub dealer_up: u8,
+    pub play: Play,
+    pub index: i32,
+    /// Played at the index or higher when true, below it when false
+    pub at_or_above: bool,
+}
+
+impl Deviation {
+    const fn new(set: DeviationSet, category: HandCategory, total: u8, dealer_up: u8, play: Play, index: i32, at_or_above: bool) -> Deviation {
+        Deviation { set, category, total, dealer_up, play, index, at_or_above }
+    }
+
+    /// "16 vs 10", "10,10 vs 5" or "11 vs A", also the key in `deviations.json`
+    pub fn name(&self) -> String {
+        let up = match self.dealer_up {
+            11 => "A".to_string(),
+            up => up.to_string(),
+        };
+        match self.category {
+            HandCategory::Pair => format!("{0},{0} vs {1}", self.total, up),
+            _ => format!("{} vs {}", self.total, up),
+        }
+    }
+
+    /// "Stand at +0 or higher" or "Hit below -1"
+    pub fn rule(&self) -> String {
+        match self.at_or_above {
+            true => format!("{} at {:+} or higher", self.play, self.index),
+            false => format!("{} below {:+}", self.play, self.index),
+        }
+    }
+
+    pub fn applies(&self, true_count: i32) -> bool {
+        (true_count >= self.index) == self.at_or_above
+    }
+
+    /// The hand as basic strategy sees it. Surrender is only on the table for the Fab 4.
+    pub fn situation(&self) -> Situation {
+        Situation {
+            category: self.category,
+            total: self.total,
+            dealer_up: self.dealer_up,
+            can_double: true,
+            can_split: true,
+            can_surrender: self.set == DeviationSet::Fab4,
+        }
+    }
+
+    /// The right play at `true_count`, basic strategy without surrender when the index isn't reached
+    pub fn correct_play(&self, true_count: i32, rules: &HouseRules) -> Play {
+        if self.applies(true_count) {
+            return self.play;
+        }
+        best_play(&Situation { can_surrender: false, ..self.situation() }, rules)
+    }
+}
+
+/// The standard Illustrious 18 and Fab 4 for a multi-deck S17 game
+pub const STANDARD_DEVIATIONS: [Deviation; 21] = {
+    use DeviationSet::{Fab4, Illustrious18};
+    use HandCategory::{Hard, Pair};
+    [
+        Deviation::new(Illustrious18, Hard, 16, 10, Play::Stand, 0, true),
+        Deviation::new(Illustrious18, Hard, 15, 10, Play::Stand, 4, true),
+        Deviation::new(Illustrious18, Pair, 10, 5, Play::Split, 5, true),
+        Deviation::new(Illustrious18, Pair, 10, 6, Play::Split, 4, true),
+        Deviation::new(Illustrious18, Hard, 10, 10, Play::Double, 4, true),
+        Deviation::new(Illustrious18, Hard, 12, 3, Play::Stand, 2, true),
+        Deviation::new(Illustrious18, Hard, 12, 2, Play::Stand, 3, true),
+        Deviation::new(Illustrious18, Hard, 11, 11, Play::Double, 1, true),
+        Deviation::new(Illustrious18, Hard, 9, 2, Play::Double, 1, true),
+        Deviation::new(Illustrious18, Hard, 10, 11, Play::Double, 4, true),
+        Deviation::new(Illustrious18, Hard, 9, 7, Play::Double, 3, true),
+        Deviation::new(Illustrious18, Hard, 16, 9, Play::Stand, 5, true),
+        Deviation::new(Illustrious18, Hard, 13, 2, Play::Hit, -1, false),
+        Deviation::new(Illustrious18, Hard, 12, 4, Play::Hit, 0, false),
+        Deviation::new(Illustrious18, Hard, 12, 5, Play::Hit, -2, false),
+        Deviation::new(Illustrious18, Hard, 12, 6, Play::Hit, -1, false),
+        Deviation::new(Illustrious18, Hard, 13, 3, Play::Hit, -2, false),
+        Deviation::new(Fab4, Hard, 14, 10, Play::Surrender, 3, true),
+        Deviation::new(Fab4, Hard, 15, 10, Play::Surrender, 0, true),
+        Deviation::new(Fab4, Hard, 15, 9, Play::Surrender, 2, true),
+        Deviation::new(Fab4, Hard, 15, 11, Play::Surrender, 1, true),
+    ]
+};
+
+/// Lowest and highest index an entry can be edited to
+pub const INDEX_RANGE: (i32, i32) = (-10, 10);
+
+/// The standard deviations with the player's own indices, saved to
+/// `deviations.json` as a map from name to index. Only changed indices are saved.
+#[derive(Clone, Debug, Default)]
+pub struct DeviationTable {
+    indices: BTreeMap<String, i32>,
+    path: Option<PathBuf>,
+}
+
+impl DeviationTable {
+    /// Reads the table in `dir`. Without a directory nothing is saved.
+    pub fn load(dir: Option<PathBuf>) -> (DeviationTable, Option<String>) {
+        let Some(path) = dir.map(|dir| dir.join("deviations.json")) else {
+            return (DeviationTable::default(), None);
+        };
+        let (indices, problem) = match fs::read_to_string(&path) {
+            Ok(contents) => match serde_json::from_str::<BTreeMap<String, i32>>(&contents) {
+                Ok(indices) => (indices, None),
+                Err(err) => (BTreeMap::new(), Some(format!("deviations.json: {}", err))),
+            },
+            Err(_) => (BTreeMap::new(), None),
+        };
+        (DeviationTable { indices, path: Some(path) }, problem)
+    }
+
+    /// Every deviation, with the player's index where they changed it
+    pub fn entries(&self) -> Vec<Deviation> {
+        STANDARD_DEVIATIONS
+            .iter()
+            .map(|deviation| match self.indices.get(&self.key(deviation)) {
+                Some(index) => Deviation { index: *index, ..*deviation },
+                None => *deviation,
+            })
+            .collect()
+    }
+
+    /// Moves the index of entry `at` by `step`, within `INDEX_RANGE`
+    pub fn step_index(&mut self, at: usize, step: i32) -> io::Result<()> {
+        let Some(deviation) = self.entries().get(at).copied() else {
+            return Ok(());
+        };
+        let index = (deviation.index + step).clamp(INDEX_RANGE.0, INDEX_RANGE.1);
+        if index == STANDARD_DEVIATIONS[at].index {
+            self.indices.remove(&self.key(&deviation));
+        } else {
+            self.indices.insert(self.key(&deviation), index);
+        }
+        self.save()
+    }
+
+    /// Back to the standard index
+    pub fn reset(&mut self, at: usize) -> io::Result<()> {
+        if let Some(deviation) = STANDARD_DEVIATIONS.get(at) {
+            self.indices.remove(&self.key(deviation));
+        }
+        self.save()
+    }
+
+    /// Entries in both sets share a hand, the Fab 4 ones are told apart by the play
+    fn key(&self, deviation: &Deviation) -> String {
+        match deviation.set {
+            DeviationSet::Fab4 => format!("{} {}", deviation.name(), deviation.play),
+            DeviationSet::Illustrious18 => deviation.name(),
+        }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(&self.indices)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::rules::HouseRules;
+    use crate::game::strategy::Play;
+    use super::{DeviationTable, STANDARD_DEVIATIONS};
+
+    #[test]
+    fn deviations_differ_from_basic_strategy_only_past_the_index() {
+        let rules = HouseRules::default();
+        for deviation in STANDARD_DEVIATIONS {
+            let index = deviation.index;
+            let (past, short) = if deviation.at_or_above { (index, index - 1) } else { (index - 1, index) };
+            assert_eq!(deviation.correct_play(past, &rules), deviation.play, "{}", deviation.name());
+            assert_ne!(deviation.correct_play(short, &rules), deviation.play, "{}", deviation.name());
+        }
+    }
+
+    #[test]
+    fn edited_indices_replace_the_standard_ones() {
+        let mut table = DeviationTable::default();
+        table.step_index(0, 2).unwrap();
+        assert_eq!(table.entries()[0].rule(), "Stand at +2 or higher");
+        assert_eq!(table.entries()[0].correct_play(1, &HouseRules::default()), Play::Hit);
+        table.reset(0).unwrap();
+        assert_eq!(table.entries()[0].index, 0);
+        // The Fab 4 15 vs 10 is kept apart from the Illustrious 18 one
+        table.step_index(18, 1).unwrap();
+        assert_eq!(table.entries()[1].index, 4);
+        assert_eq!(table.entries()[18].index, 1);
+    }
+}
@@ -1,30 +1,130 @@
-use std::arch::aarch64::vreinterpret_u8_f32;
 use std::rc::Rc;
-use std::thread::sleep;
-use std::time::Duration;
-use ratatui::crossterm::event;
-use ratatui::crossterm::event::{Event, KeyCode};
+use ratatui::crossterm::event::KeyCode;
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, Paragraph};
+use blackjack_engine::card::Card;
 use blackjack_engine::game::{Game, GameAction, GameState};
 use blackjack_engine::game_settings::GameSettings;
 use blackjack_engine::hand::Hand;
+use crate::event::AppEvent;
+use crate::keybindings::{bindings, key_label, Action, Context};
+use crate::game::bet_ramp::{betting_count, BetRamp};
+use crate::game::count::CountSystem;
+use crate::game::count_quiz::CountQuiz;
+use crate::game::rules::{net_result, HouseRules};
+use crate::game::shoe::ShoeTracker;
+use crate::game::strategy::{best_play, HandCategory, HintMode, Play, Situation};
+use crate::game::trainer::{Grade, Trainer};
+use crate::game::animation::{sprite_rect, AnimationKind, Animator, HoleCardFace, Seat};
 use crate::model::{Model, ModelResponse};
-use crate::ui::{render_border, render_bottom_right_text, render_bottom_text, render_footer_spans, render_text};
+use crate::navigation::Navigation;
+use crate::overlay::help::HelpOverlay;
+use crate::settings::config::Settings;
+use crate::theme::theme;
+use crate::overlay::modal::{Modal, ModalResult};
+use crate::ui::{card_span, footer_lines, render_border, render_bottom_right_text, render_bottom_text, render_footer_hints, render_buttons, render_line, render_text, FooterHint};
 
 pub struct GameScreen {
+    player_name: String,
     dealer_name: String,
     dealer_message: String,
     input_prompt: String,
     cursor_string: String,
     user_bet: f64,
     game: Game,
+    rules: HouseRules,
+    /// Whether quitting mid-hand asks first
+    confirm_quit: bool,
+    hint_mode: HintMode,
+    /// The hint key was pressed for the decision at hand
+    hint_requested: bool,
+    /// Set in training mode, grades every decision
+    trainer: Option<Trainer>,
+    /// A graded mistake waiting for the player to take it back or play it anyway
+    pending_action: Option<Action>,
+    /// A mistake was taken back, the decision that replaces it is graded but already scored
+    retrying: bool,
+    /// Kept in the stats area when set
+    count_system: Option<CountSystem>,
+    /// Suggests a bet for the count, `None` when the bet spread is off
+    bet_ramp: Option<BetRamp>,
+    shoe: ShoeTracker,
+    /// Set in count practice, asks for the hidden count now and then
+    count_quiz: Option<CountQuiz>,
+    /// The round ended on a count check, asked once the table stops moving
+    quiz_due: bool,
+    /// The running count answered, waiting for the true count
+    quiz_running: Option<i32>,
+    /// The player places the cut card after each shuffle
+    player_cuts: bool,
+    /// The shoe was shuffled, the player is asked for the cut card once the table stops moving
+    cut_due: bool,
+    /// The engine refilled the shoe during this round, told once the round is cleared
+    shoe_ran_out: bool,
     bankroll: f64,
+    animator: Animator,
+    ticks_since_blink: u16,
+    /// Where the chips and action buttons were last drawn, for mouse clicks
+    chip_rects: Vec<(Rect, u32)>,
+    button_rects: Vec<(Rect, Action)>,
 }
 
+/// Position of the dealer's face down card in the dealer hand
+const HOLE_CARD_INDEX: usize = 1;
+
+/// The footer grows up to this many lines on narrow terminals before it starts shortening hints
+const MAX_FOOTER_LINES: u16 = 3;
+
+/// Chips that can be clicked to add to the bet
+const CHIPS: [u32; 5] = [1, 5, 25, 100, 500];
+
+/// How much one notch of the scroll wheel changes the bet
+const BET_SCROLL_STEP: f64 = 5.0;
+
+/// Actions with an on-screen button during the player's turn
+const ACTION_BUTTONS: [Action; 4] = [Action::Hit, Action::Stand, Action::Double, Action::Split];
+
+/// Below this size the table drops its margins and the shoe to fit
+const COMPACT_WIDTH: u16 = 100;
+const COMPACT_HEIGHT: u16 = 32;
+
+/// Ticks between two blinks of the bet cursor (~500ms)
+const CURSOR_BLINK_TICKS: u16 = 15;
+
+const FORFEIT_MODAL: &str = "forfeit";
+const LEAVE_TABLE_MODAL: &str = "leave_table";
+const LEAVE_TABLE_OPTIONS: [&str; 3] = ["Keep playing", "Back to menu", "Quit"];
+const MISTAKE_MODAL: &str = "mistake";
+const RUNNING_COUNT_MODAL: &str = "running_count";
+const TRUE_COUNT_MODAL: &str = "true_count";
+const CUT_CARD_MODAL: &str = "cut_card";
+
+/// Where the player may place the cut card, in percent of the shoe
+const CUT_CARD_RANGE: (u32, u32) = (50, 90);
+
 // Layout-related functions
 impl GameScreen {
-    fn create_main_layout(screen: Rect) ->  Rc<[Rect]> {
+    /// Whether `screen` needs the condensed table
+    fn is_compact(screen: Rect) -> bool {
+        screen.width < COMPACT_WIDTH || screen.height < COMPACT_HEIGHT
+    }
+
+    fn create_main_layout(screen: Rect, footer_height: u16) ->  Rc<[Rect]> {
+        if Self::is_compact(screen) {
+            // Dealer, player and stats stacked on top of each other, no margins
+            return Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(0),              // Top margin
+                    Constraint::Fill(1),                // Dealer area
+                    Constraint::Fill(1),                // Player area
+                    Constraint::Length(3),              // Stats/bankroll area
+                    Constraint::Length(footer_height),  // Footer
+                ])
+                .split(screen);
+        }
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
@@ -32,26 +132,27 @@ impl GameScreen {
                 Constraint::Ratio(4, 10),           // Dealer area
                 Constraint::Ratio(4, 10),           // Player area
                 Constraint::Ratio(1, 10),           // Stats/bankroll area
-                Constraint::Length(1),              // Footer
+                Constraint::Length(footer_height),  // Footer
             ])
             .split(screen)
     }
 
-    fn create_dealer_section(dealer_area: Rect) -> (Rc<[Rect]>, Rc<[Rect]>) {
-        Self::create_default_playable_section(dealer_area)
+    fn create_dealer_section(dealer_area: Rect, compact: bool) -> (Rc<[Rect]>, Rc<[Rect]>) {
+        Self::create_default_playable_section(dealer_area, compact)
     }
 
-    fn create_player_section(player_area: Rect) -> (Rc<[Rect]>, Rc<[Rect]>) {
-        Self::create_default_playable_section(player_area)
+    fn create_player_section(player_area: Rect, compact: bool) -> (Rc<[Rect]>, Rc<[Rect]>) {
+        Self::create_default_playable_section(player_area, compact)
     }
 
-    fn create_default_playable_section(playable_area: Rect) -> (Rc<[Rect]>, Rc<[Rect]>) {
+    fn create_default_playable_section(playable_area: Rect, compact: bool) -> (Rc<[Rect]>, Rc<[Rect]>) {
+        let side = if compact { 0 } else { 2 };
         let horizontal = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([
-                    Constraint::Ratio(2, 10),
-                    Constraint::Ratio(6, 10),
-                    Constraint::Ratio(2, 10),
+                    Constraint::Ratio(side, 10),
+                    Constraint::Ratio(10 - 2 * side, 10),
+                    Constraint::Ratio(side, 10),
                 ])
                 .split(playable_area);
 
@@ -67,18 +168,34 @@ impl GameScreen {
         (horizontal, vertical)
     }
 
+    /// Where hand `i` goes. The first two hands get a row each, hands made
+    /// by splitting again go a line at a time under the second.
+    fn hand_rect(player_vertical: &[Rect], i: usize) -> Rect {
+        let row = player_vertical[2];
+        match player_vertical.get(i + 1) {
+            Some(rect) if i < 2 => *rect,
+            _ => Rect { y: (row.y + (i - 1) as u16).min(row.bottom().saturating_sub(2)), height: 1, ..row },
+        }
+    }
+
 
 
-    fn create_stats_section(stats_area: Rect) -> Rc<[Rect]> {
+    fn create_shoe_section(shoe_area: Rect) -> Rect {
+        Rect {
+            y: shoe_area.y + shoe_area.height / 2,
+            height: 1,
+            ..shoe_area
+        }
+    }
+
+    /// Margins on either side of `boxes` equally wide stats boxes
+    fn create_stats_section(stats_area: Rect, boxes: usize) -> Rc<[Rect]> {
+        let mut constraints = vec![Constraint::Ratio(1, 20)];   // Left margin
+        constraints.extend((0..boxes).map(|_| Constraint::Fill(1)));
+        constraints.push(Constraint::Ratio(1, 20));             // Right margin
         Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Ratio(1, 20),           // Left margin
-                Constraint::Ratio(6, 20),           // Stats 1
-                Constraint::Ratio(6, 20),           // Stats 2
-                Constraint::Ratio(6, 20),           // Stats 3
-                Constraint::Ratio(1, 20),           // Right margin
-            ])
+            .constraints(constraints)
             .split(stats_area)
     }
 
@@ -98,9 +215,9 @@ impl GameScreen {
 impl GameScreen {
     fn render_player_section(&self, frame: &mut Frame, player_wrapper: Rc<[Rect]>, player_rect: Rc<[Rect]>) {
         render_border(frame, player_wrapper[1]);
-        render_text(frame, player_wrapper[1], " Jack ");
-        render_bottom_text(frame, player_wrapper[1], format!(" Bet: ${} ", self.user_bet.to_string()).as_str());
-        render_bottom_right_text(frame, player_wrapper[1], format!(" Bank: ${} ", self.bankroll.to_string()).as_str());
+        render_text(frame, player_wrapper[1], format!(" {} ", self.player_name).as_str());
+        render_bottom_text(frame, player_wrapper[1], format!(" Bet: ${} ", self.user_bet).as_str());
+        render_bottom_right_text(frame, player_wrapper[1], format!(" Bank: ${} ", self.bankroll).as_str());
 
         self.render_player_hands(frame, player_rect);
     }
@@ -123,23 +240,8 @@ impl GameScreen {
                 }
 
                 for (i, hand) in player_hands.iter().enumerate() {
-                    let hand_text = match &hand.outcome {
-                        Some(outcome) => {
-                            if i == active_hand_index {
-                                format!("Cards {} - {} <", hand.to_string(), outcome.to_string().as_str())
-                            } else {
-                                format!("Cards {} - {}", hand.to_string(), outcome.to_string().as_str())
-                            }
-                        },
-                        None => {
-                            if i == active_hand_index {
-                                format!("Cards {} <", hand.to_string())
-                            } else {
-                                format!("Cards {}", hand.to_string())
-                            }
-                        },
-                    };
-                    render_text(frame, player_vertical[i + 1], &hand_text);
+                    let hand_line = self.hand_line(hand, Seat::Player(i), i == active_hand_index);
+                    render_line(frame, Self::hand_rect(&player_vertical, i), hand_line);
                 }
             },
             GameState::DealerTurn { player_hands, ..} | GameState::RoundComplete {player_hands, ..}=> {
@@ -149,235 +251,1228 @@ impl GameScreen {
                 }
 
                 for (i, hand) in player_hands.iter().enumerate() {
-                    let hand_text = match &hand.outcome {
-                        Some(outcome) => {
-                            format!("Cards {} - {}", hand.to_string(), outcome.to_string().as_str())
-                        },
-                        None => {
-                            format!("Cards {}", hand.to_string())
-                        },
-                    };
-                    render_text(frame, player_vertical[i + 1], &hand_text);
+                    let hand_line = self.hand_line(hand, Seat::Player(i), false);
+                    render_line(frame, Self::hand_rect(&player_vertical, i), hand_line);
                 }
             }
             _ => {}
         }
     }
 
-    fn render_stats_section(&self, frame: &mut Frame, stats_rects: Rc<[Rect]>) {
-        for rect in &stats_rects[0..=4] {
+    /// Title and value of each stats box: the training scores, then the count
+    fn stat_panels(&self) -> Vec<(String, String)> {
+        let mut panels = vec![];
+        if let Some(trainer) = &self.trainer {
+            for category in [HandCategory::Hard, HandCategory::Soft, HandCategory::Pair] {
+                panels.push((format!(" {} ", category), trainer.score(category).to_string()));
+            }
+            if self.bet_ramp.is_some() {
+                panels.push((" Bets ".to_string(), trainer.bets().on_ramp.to_string()));
+            }
+        }
+        if let Some(quiz) = &self.count_quiz {
+            panels.push((" Running count ".to_string(), quiz.session().running.to_string()));
+            if quiz.system().is_balanced() {
+                panels.push((" True count ".to_string(), quiz.session().true_count.to_string()));
+            }
+        }
+        if let Some(system) = self.count_system {
+            let (running_count, true_count) = self.counts(system);
+            let true_count = match true_count {
+                Some(true_count) => format!("{:+.1}", true_count),
+                // Unbalanced counts are played off the running count alone
+                None => "n/a".to_string(),
+            };
+            panels.push((format!(" {} ", system), format!("RC {:+}", running_count)));
+            panels.push((" True count ".to_string(), true_count));
+            panels.push((" Decks left ".to_string(), format!("{:.1}", self.shoe.decks_remaining())));
+        }
+        panels
+    }
+
+    fn render_stats_section(&self, frame: &mut Frame, stats_area: Rect) {
+        let panels = self.stat_panels();
+        // Three empty boxes when there is nothing to keep track of
+        let stats_rects = Self::create_stats_section(stats_area, panels.len().max(3));
+        for rect in stats_rects.iter() {
             render_border(frame, *rect);
         }
+        for (rect, (title, value)) in stats_rects[1..].iter().zip(panels) {
+            // Narrow boxes keep the first word of the title and their corners
+            let title = match title.trim().split_once(' ') {
+                Some((first, _)) if title.chars().count() as u16 + 2 > rect.width => format!(" {} ", first),
+                _ => title,
+            };
+            render_text(frame, *rect, &title);
+            let inner = Rect::new(rect.x + 1, rect.y + rect.height / 2, rect.width.saturating_sub(2), 1);
+            render_line(frame, inner, Line::from(value).centered());
+        }
     }
 
+    /// "Cards" followed by the cards that have finished sliding in from the
+    /// shoe, the outcome once there is one, and a marker on the active hand
+    fn hand_line(&self, hand: &Hand, seat: Seat, active: bool) -> Line<'static> {
+        let landed = hand.cards.len().saturating_sub(self.animator.pending_deals(seat));
+        let mut spans = vec![Span::raw("Cards")];
+        for card in &hand.cards[..landed] {
+            spans.push(Span::raw(" "));
+            spans.push(card_span(card));
+        }
+        if let Some(outcome) = &hand.outcome {
+            spans.push(Span::raw(" - "));
+            spans.push(Span::styled(outcome.to_string(), theme().outcome(outcome)));
+        }
+        if active {
+            spans.push(Span::raw(" <"));
+        }
+        Line::from(spans)
+    }
+
+    /// Chips while betting, action buttons while playing. Remembers where they
+    /// went so clicks can be matched to them.
+    fn render_controls(&mut self, frame: &mut Frame, player_wrapper: Rect, player_vertical: Rc<[Rect]>) {
+        self.chip_rects.clear();
+        self.button_rects.clear();
+        match *self.game.get_state() {
+            GameState::WaitingForBet { .. } => {
+                let labels = CHIPS.iter().map(|chip| (format!("${}", chip), true)).collect::<Vec<_>>();
+                let rects = render_buttons(frame, player_vertical[2], &labels);
+                self.chip_rects = rects.into_iter().zip(CHIPS).collect();
+            }
+            GameState::PlayerTurn { .. } => {
+                // The row just above the bottom border, under the hands
+                let row = Rect {
+                    x: player_wrapper.x + 1,
+                    y: player_wrapper.bottom().saturating_sub(2),
+                    width: player_wrapper.width.saturating_sub(2),
+                    height: 1,
+                };
+                let labels = ACTION_BUTTONS
+                    .iter()
+                    .map(|action| (format!("{} {}", bindings().key_hint(*action), action.label()), self.is_legal(*action)))
+                    .collect::<Vec<_>>();
+                let rects = render_buttons(frame, row, &labels);
+                self.button_rects = rects.into_iter().zip(ACTION_BUTTONS).collect();
+
+                if let Some(hint) = self.hint() {
+                    let hint_row = Rect { y: row.y.saturating_sub(1), ..row };
+                    render_line(frame, hint_row, Line::from(Span::styled(hint, theme().highlight())).centered());
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 // Rendering-Dealer functions
 impl GameScreen {
     fn render_dealer_section(&self, frame: &mut Frame, dealer_wrapper: Rc<[Rect]>, dealer_rect: Rc<[Rect]>) {
         render_border(frame, dealer_wrapper[1]);
+        render_text(frame, dealer_wrapper[1], format!(" {} ", self.dealer_name).as_str());
+        render_text(frame, Self::create_shoe_section(dealer_wrapper[0]), "[Tray]");
+        render_text(frame, Self::create_shoe_section(dealer_wrapper[2]), "[Shoe]");
         match (*self.game.get_state()).clone() {
             GameState::WaitingForBet { .. } => {
                 render_text(frame, dealer_rect[1], "Place your bet");
+                if let Some(suggestion) = self.bet_suggestion() {
+                    render_line(frame, dealer_rect[2], Line::from(Span::styled(suggestion, theme().highlight())).centered());
+                }
             },
             GameState::PlayerTurn { dealer_hand, .. } => {
                 render_text(frame, dealer_rect[1], "Make your moves");
-                self.render_hand(frame, dealer_rect[2], Hand::with_card(dealer_hand.cards[0].clone()));
+                self.render_hand(frame, dealer_rect[2], dealer_hand, HoleCardFace::Down);
             },
             GameState::RoundComplete { dealer_hand, .. } => {
-                render_text(frame, dealer_rect[1], "All done");
-                self.render_hand(frame, dealer_rect[2], dealer_hand);
+                let message = if self.shoe.needs_shuffle() { "All done, the cut card is out" } else { "All done" };
+                render_text(frame, dealer_rect[1], message);
+                self.render_hand(frame, dealer_rect[2], dealer_hand, self.animator.hole_card_face());
             },
-            GameState::DealerTurn { dealer_hand, .. } | GameState::RoundComplete {dealer_hand, ..} => {
+            GameState::DealerTurn { dealer_hand, .. } => {
                 render_text(frame, dealer_rect[1], "Looks like I'm up");
-                self.render_hand(frame, dealer_rect[2], dealer_hand);
+                self.render_hand(frame, dealer_rect[2], dealer_hand, self.animator.hole_card_face());
             },
             _ => {}
         }
     }
 
-    fn render_hand(&self, frame: &mut Frame, rect: Rect, hand: Hand) {
-        let hand_text = hand.to_string();
-        render_text(frame, rect, &hand_text);
+    fn render_hand(&self, frame: &mut Frame, rect: Rect, hand: Hand, hole_card: HoleCardFace) {
+        let landed = hand.cards.len().saturating_sub(self.animator.pending_deals(Seat::Dealer));
+        let mut spans = vec![];
+        for (i, card) in hand.cards[..landed].iter().enumerate() {
+            if i > 0 {
+                spans.push(Span::raw(" "));
+            }
+            spans.push(match hole_card {
+                HoleCardFace::Down if i == HOLE_CARD_INDEX => Span::raw("??"),
+                HoleCardFace::Edge if i == HOLE_CARD_INDEX => Span::raw("|"),
+                _ => card_span(card),
+            });
+        }
+        render_line(frame, rect, Line::from(spans));
     }
 }
 
+// Animation-related functions
 impl GameScreen {
-    pub fn new() -> GameScreen {
+    /// Number of cards held by the dealer and by each player hand
+    fn card_counts(state: &GameState) -> (usize, Vec<usize>) {
+        match state {
+            GameState::PlayerTurn { dealer_hand, player_hands, .. }
+            | GameState::DealerTurn { dealer_hand, player_hands, .. }
+            | GameState::RoundComplete { dealer_hand, player_hands, .. } => {
+                (dealer_hand.cards.len(), player_hands.iter().map(|h| h.cards.len()).collect())
+            },
+            _ => (0, vec![]),
+        }
+    }
+
+    /// Run an engine call and queue up animations for whatever it changed on the table
+    fn apply(&mut self, action: impl FnOnce(&mut Game)) {
+        let before = (*self.game.get_state()).clone();
+        action(&mut self.game);
+        self.queue_animations(&before);
+        self.shoe.observe(&Self::face_up_cards(self.game.get_state()));
+        self.shoe_ran_out |= self.shoe.refill_if_emptied();
+
+        let round_over = matches!(*self.game.get_state(), GameState::RoundComplete { .. })
+            && !matches!(before, GameState::RoundComplete { .. });
+        if let (true, Some(quiz)) = (round_over, self.count_quiz.as_mut()) {
+            self.quiz_due = quiz.should_ask(self.shoe.needs_shuffle());
+        }
+    }
+
+    /// Every card on the table the player can see, the hole card once it is turned over
+    fn face_up_cards(state: &GameState) -> Vec<Card> {
+        match state {
+            GameState::PlayerTurn { dealer_hand, player_hands, .. } => dealer_hand
+                .cards
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != HOLE_CARD_INDEX)
+                .map(|(_, card)| card)
+                .chain(player_hands.iter().flat_map(|hand| &hand.cards))
+                .cloned()
+                .collect(),
+            GameState::DealerTurn { dealer_hand, player_hands, .. }
+            | GameState::RoundComplete { dealer_hand, player_hands, .. } => dealer_hand
+                .cards
+                .iter()
+                .chain(player_hands.iter().flat_map(|hand| &hand.cards))
+                .cloned()
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    fn queue_animations(&mut self, before: &GameState) {
+        let after = (*self.game.get_state()).clone();
+        let (dealer_before, hands_before) = Self::card_counts(before);
+        let (dealer_after, hands_after) = Self::card_counts(&after);
+
+        // A split moves one card into the new hand, only the top-up cards come from the shoe
+        let split_index = match before {
+            GameState::PlayerTurn { active_hand_index, .. }
+                if !hands_before.is_empty() && hands_after.len() > hands_before.len() => Some(*active_hand_index),
+            _ => None,
+        };
+        let mut seats: Vec<(Seat, usize, usize)> = hands_after
+            .iter()
+            .enumerate()
+            .map(|(i, &total)| {
+                let dealt = match split_index {
+                    Some(split) if i == split || i == split + 1 => 1,
+                    _ => hands_before.get(i).copied().unwrap_or(0),
+                };
+                (Seat::Player(i), dealt, total)
+            })
+            .collect();
+        seats.push((Seat::Dealer, dealer_before, dealer_after));
+
+        // Cards go out one seat at a time, the way a dealer would pitch them
+        let mut dealing = true;
+        while dealing {
+            dealing = false;
+            for (seat, dealt, total) in seats.iter_mut() {
+                if dealt < total {
+                    let face_up = !(*seat == Seat::Dealer && *dealt == HOLE_CARD_INDEX);
+                    self.animator.push(AnimationKind::Deal { seat: *seat, face_up });
+                    *dealt += 1;
+                    dealing = true;
+                }
+            }
+        }
+
+        let hole_card_hidden = matches!(before, GameState::WaitingToDeal { .. } | GameState::PlayerTurn { .. });
+        let hole_card_shown = matches!(after, GameState::DealerTurn { .. } | GameState::RoundComplete { .. });
+        if hole_card_hidden && hole_card_shown {
+            self.animator.push(AnimationKind::FlipHoleCard);
+        }
+
+        if let GameState::RoundComplete { player_hands, .. } = after {
+            if !matches!(before, GameState::RoundComplete { .. }) {
+                let net = net_result(&player_hands);
+                if net != 0f64 {
+                    self.animator.push(AnimationKind::MoveChips { amount: net.abs(), to_player: net > 0f64 });
+                }
+            }
+        }
+    }
+
+    /// The card currently travelling to `seat`
+    fn card_in_flight(&self, seat: Seat) -> Option<Card> {
+        let cards = match (*self.game.get_state()).clone() {
+            GameState::PlayerTurn { dealer_hand, player_hands, .. }
+            | GameState::DealerTurn { dealer_hand, player_hands, .. }
+            | GameState::RoundComplete { dealer_hand, player_hands, .. } => match seat {
+                Seat::Dealer => dealer_hand.cards,
+                Seat::Player(i) => player_hands.get(i)?.cards.clone(),
+            },
+            _ => return None,
+        };
+        let index = cards.len().checked_sub(self.animator.pending_deals(seat))?;
+        cards.get(index).cloned()
+    }
+
+    fn render_animation(
+        &self,
+        frame: &mut Frame,
+        dealer_horizontal: Rc<[Rect]>,
+        dealer_vertical: Rc<[Rect]>,
+        player_horizontal: Rc<[Rect]>,
+        player_vertical: Rc<[Rect]>,
+    ) {
+        let Some(animation) = self.animator.current() else {
+            return;
+        };
+        let shoe = Self::create_shoe_section(dealer_horizontal[2]);
+        let bet_spot = Rect {
+            y: player_horizontal[1].y + player_horizontal[1].height.saturating_sub(1),
+            height: 1,
+            width: player_horizontal[1].width / 3,
+            ..player_horizontal[1]
+        };
+
+        let (label, from, to) = match &animation.kind {
+            AnimationKind::Deal { seat, face_up } => {
+                let target = match seat {
+                    Seat::Dealer => dealer_vertical[2],
+                    Seat::Player(i) => Self::hand_rect(&player_vertical, *i),
+                };
+                let face = match self.card_in_flight(*seat) {
+                    Some(card) if *face_up => card.to_string(),
+                    _ => "??".to_string(),
+                };
+                (format!("[{}]", face), shoe, target)
+            },
+            AnimationKind::MoveChips { amount, to_player } => {
+                let label = format!("(${})", amount);
+                if *to_player {
+                    (label, dealer_vertical[1], bet_spot)
+                } else {
+                    (label, bet_spot, dealer_vertical[1])
+                }
+            },
+            AnimationKind::BurnCard => ("[??]".to_string(), shoe, Self::create_shoe_section(dealer_horizontal[0])),
+            AnimationKind::Shuffle => {
+                let row = dealer_vertical[1];
+                frame.render_widget(Clear, row);
+                render_line(frame, row, Line::from(Span::styled("Shuffling the shoe", theme().highlight())).centered());
+                return;
+            }
+            AnimationKind::FlipHoleCard | AnimationKind::Pause => return,
+        };
+
+        let sprite = sprite_rect(from, to, animation.progress(), label.chars().count() as u16)
+            .intersection(frame.area());
+        frame.render_widget(Clear, sprite);
+        frame.render_widget(Paragraph::new(label), sprite);
+    }
+}
+
+impl GameScreen {
+    pub fn new(settings: &Settings, player_name: String) -> GameScreen {
+        let rules = HouseRules { decks: settings.decks(), ..HouseRules::default() };
+        let mut shoe = ShoeTracker::new(rules.decks, rules.penetration);
         let mut game = Game::new(
-            GameSettings::new("Jack".to_string(), 6)
+            GameSettings::new(player_name.clone(), rules.decks)
         );
         game.shuffle_shoe();
+        shoe.burn();
         GameScreen {
+            player_name,
             dealer_name: String::from("Dealer McGee"),
             dealer_message: String::from("PLACE YOUR BET"),
             input_prompt: String::from("BET: $"),
             cursor_string: String::from("█"),
-            user_bet: 0f64,
+            user_bet: settings.default_bet(),
             game,
+            rules,
+            confirm_quit: settings.confirm_quit(),
+            hint_mode: settings.hint_mode(),
+            hint_requested: false,
+            trainer: None,
+            pending_action: None,
+            retrying: false,
+            count_system: settings.count_system(),
+            bet_ramp: settings.bet_ramp(),
+            shoe,
+            count_quiz: None,
+            quiz_due: false,
+            quiz_running: None,
+            player_cuts: settings.player_cuts(),
+            cut_due: settings.player_cuts(),
+            shoe_ran_out: false,
             bankroll: 0f64,
+            animator: Animator::new(settings.animation_speed()),
+            ticks_since_blink: 0,
+            chip_rects: vec![],
+            button_rects: vec![],
         }
     }
 
-    pub fn handle_waiting_for_bet(&mut self, bankroll: f64) -> std::io::Result<ModelResponse> {
+    /// Training mode: every decision is graded against basic strategy before it is played
+    pub fn with_trainer(mut self) -> GameScreen {
+        self.trainer = Some(Trainer::new());
+        self
+    }
+
+    /// Count practice: the count is kept hidden and asked for at the end of some
+    /// rounds, with the cards coming out at the dealer speed
+    pub fn with_count_quiz(mut self, settings: &Settings) -> GameScreen {
+        let system = settings.count_system().unwrap_or(CountSystem::HiLo);
+        self.animator = Animator::new(settings.dealer_speed());
+        self.count_quiz = Some(CountQuiz::new(system, settings.dealer_speed(), settings.storage_dir()));
+        self.count_system = None;
+        self
+    }
+
+    pub fn handle_waiting_for_bet(&mut self, bankroll: f64, event: &AppEvent) -> std::io::Result<ModelResponse> {
         self.bankroll = bankroll;
         self.dealer_message = "PLACE YOUR BET".to_string();
         self.input_prompt = "BET: $".to_string();
-        match self.cursor_string.as_str() {
-            "█" => self.cursor_string = String::from(" "),
-            " " => self.cursor_string = String::from("█"),
-            _ => {}
-        }
 
-        // Poll for events with a timeout of 500ms
-        if event::poll(Duration::from_millis(500))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == event::KeyEventKind::Release {
-                    return Ok(ModelResponse::Refresh);
+        if let Some(position) = event.left_click() {
+            let Some((_, chip)) = self.chip_rects.iter().find(|(rect, _)| rect.contains(position)) else {
+                return Ok(ModelResponse::NoOp);
+            };
+            self.user_bet += *chip as f64;
+            return Ok(ModelResponse::Refresh);
+        }
+        if let Some(scroll) = event.scroll() {
+            self.user_bet = (self.user_bet + BET_SCROLL_STEP * scroll as f64).max(0f64);
+            return Ok(ModelResponse::Refresh);
+        }
+        let key = match event {
+            AppEvent::Tick => return Ok(self.blink_cursor()),
+            AppEvent::Key(key) => key,
+            _ => return Ok(ModelResponse::NoOp),
+        };
+        // Digits and Backspace always edit the bet, whatever the bindings say
+        match key.code {
+            KeyCode::Char(ch) if ch.is_ascii_digit() => {
+                // Convert char to digit and multiply existing bet by 10
+                if let Some(digit) = ch.to_digit(10) {
+                    self.user_bet = self.user_bet * 10.0 + digit as f64;
                 }
-                return match key.code {
-                    KeyCode::Char('0')
-                    | KeyCode::Char('1')
-                    | KeyCode::Char('2')
-                    | KeyCode::Char('3')
-                    | KeyCode::Char('4')
-                    | KeyCode::Char('5')
-                    | KeyCode::Char('6')
-                    | KeyCode::Char('7')
-                    | KeyCode::Char('8')
-                    | KeyCode::Char('9') => {
-                        if let KeyCode::Char(ch) = key.code {
-                            // Convert char to digit and multiply existing bet by 10
-                            if let Some(digit) = ch.to_digit(10) {
-                                self.user_bet = self.user_bet * 10.0 + digit as f64;
-                            }
-                        }
-                        Ok(ModelResponse::Refresh)
-                    },
-                    KeyCode::Backspace => {
-                        self.user_bet = (self.user_bet / 10f64).floor();
-                        Ok(ModelResponse::Refresh)
-                    },
-                    KeyCode::Enter => {
-                        self.game.accept_user_bet(self.user_bet);
-                        self.bankroll = self.bankroll - self.user_bet;
-                        Ok(ModelResponse::Refresh)
-                    },
-                    KeyCode::Char('m') | KeyCode::Up => {
-                        Ok(ModelResponse::NavToMainMenu)
-                    }
-                    KeyCode::Char('q') | KeyCode::Up => {
-                        Ok(ModelResponse::Exit)
-                    }
-                    _ => Ok(ModelResponse::Refresh),
+                return Ok(ModelResponse::Refresh);
+            },
+            KeyCode::Backspace => {
+                self.user_bet = (self.user_bet / 10f64).floor();
+                return Ok(ModelResponse::Refresh);
+            },
+            _ => {}
+        }
+        match bindings().action(Context::Betting, key) {
+            Some(Action::PlaceBet) => {
+                if self.user_bet <= 0f64 {
+                    return Ok(ModelResponse::Toast("Type in a bet first".to_string()));
                 }
+                let graded = self.grade_bet();
+                self.game.accept_user_bet(self.user_bet);
+                self.bankroll -= self.user_bet;
+                Ok(graded.map(ModelResponse::Toast).unwrap_or(ModelResponse::Refresh))
+            },
+            Some(Action::MainMenu) => {
+                Ok(ModelResponse::Navigate(Navigation::PopToRoot))
+            }
+            Some(Action::Back) => {
+                Ok(ModelResponse::Navigate(Navigation::Pop))
             }
+            Some(Action::Quit) => {
+                Ok(ModelResponse::Exit)
+            }
+            _ => Ok(ModelResponse::NoOp),
         }
-        Ok(ModelResponse::Refresh)
     }
 
-    pub fn handle_waiting_to_deal(&mut self, bankroll: f64, bet: f64) -> std::io::Result<ModelResponse> {
+    /// Toggles the bet cursor every `CURSOR_BLINK_TICKS` ticks
+    fn blink_cursor(&mut self) -> ModelResponse {
+        self.ticks_since_blink += 1;
+        if self.ticks_since_blink < CURSOR_BLINK_TICKS {
+            return ModelResponse::NoOp;
+        }
+        self.ticks_since_blink = 0;
+        match self.cursor_string.as_str() {
+            "█" => self.cursor_string = String::from(" "),
+            " " => self.cursor_string = String::from("█"),
+            _ => {}
+        }
+        ModelResponse::Refresh
+    }
+
+    pub fn handle_waiting_to_deal(&mut self) -> std::io::Result<ModelResponse> {
         self.dealer_message = "DEALING...".to_string();
         self.input_prompt = "".to_string();
         self.cursor_string = "".to_string();
-        self.game.deal_initial_cards();
+        self.apply(|game| game.deal_initial_cards());
 
         Ok(ModelResponse::Refresh)
     }
 
-    pub fn handle_player_turn(&mut self)  -> std::io::Result<ModelResponse> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Release {
-                return Ok(ModelResponse::Refresh);
+    pub fn handle_player_turn(&mut self, event: &AppEvent) -> std::io::Result<ModelResponse> {
+        let action = match event {
+            AppEvent::Key(key) => bindings().action(Context::PlayerTurn, key),
+            _ => event.left_click().and_then(|position| {
+                // Greyed out buttons can't be clicked
+                self.button_rects
+                    .iter()
+                    .find(|(rect, action)| rect.contains(position) && self.is_legal(*action))
+                    .map(|(_, action)| *action)
+            }),
+        };
+        match action {
+            Some(action @ (Action::Double | Action::Split)) if !self.is_legal(action) => {
+                Ok(ModelResponse::Toast(format!("Can't {} this hand", action.label().to_lowercase())))
             }
-            match key.code {
-                KeyCode::Char('h') => {
-                    self.game.process_player_action(GameAction::Hit, 0);
-                },
-                KeyCode::Char('s') => {
-                    self.game.process_player_action(GameAction::Stand, 0);
-                },
-                KeyCode::Char('d') => {
-                    self.game.process_player_action(GameAction::Double, 0);
-                },
-                KeyCode::Char('p') => {
-                    self.game.process_player_action(GameAction::Split, 0);
-                },
-                KeyCode::Char('m') => {
-                    return Ok(ModelResponse::NavToMainMenu);
-                }
-                KeyCode::Char('q') => {
-                    return Ok(ModelResponse::Exit);
+            Some(action @ (Action::Hit | Action::Stand | Action::Double | Action::Split)) => Ok(self.play(action)),
+            Some(Action::Hint) => {
+                if self.hint_mode != HintMode::OnRequest {
+                    return Ok(ModelResponse::NoOp);
                 }
-                _ => {}
+                self.hint_requested = true;
+                Ok(ModelResponse::Refresh)
+            }
+            Some(Action::HintMode) => {
+                self.hint_mode = self.hint_mode.next();
+                Ok(ModelResponse::Toast(format!("Strategy hints: {}", self.hint_mode.label())))
             }
+            Some(Action::MainMenu) => Ok(ModelResponse::Navigate(Navigation::PopToRoot)),
+            Some(Action::Back) => Ok(ModelResponse::Navigate(Navigation::Pop)),
+            Some(Action::Quit) if !self.confirm_quit => Ok(ModelResponse::Exit),
+            Some(Action::Quit) => {
+                Ok(ModelResponse::OpenModal(Modal::confirm(
+                    FORFEIT_MODAL,
+                    "Quit",
+                    "Quitting now forfeits the hand in play. Quit anyway?",
+                )))
+            }
+            _ => Ok(ModelResponse::NoOp),
         }
-        Ok(ModelResponse::Refresh)
     }
 
-    pub fn handle_dealer_turn(&mut self) -> std::io::Result<ModelResponse> {
-        self.game.next_dealer_turn();
+    /// Sends `action` to the game. In training mode it is graded and scored
+    /// first, and a mistake is held back until the player decides what to do
+    /// about it. Only the first try at a decision is scored.
+    fn play(&mut self, action: Action) -> ModelResponse {
+        self.hint_requested = false;
+        let situation = self.situation();
+        if let (Some(trainer), Some(situation)) = (self.trainer.as_mut(), situation) {
+            let played = strategy_play(action);
+            if !self.retrying {
+                trainer.record(&situation, played, &self.rules);
+            }
+            if let Grade::Mistake { best, explanation } = trainer.grade(&situation, played, &self.rules) {
+                self.pending_action = Some(action);
+                return ModelResponse::OpenModal(Modal::choice(
+                    MISTAKE_MODAL,
+                    &format!("Basic strategy says {}", best),
+                    &explanation,
+                    vec!["Take it back".to_string(), format!("{} anyway", played)],
+                ));
+            }
+        }
+        self.send(action);
+        ModelResponse::Refresh
+    }
+
+    /// Hands a player action to the game engine, without grading it
+    fn send(&mut self, action: Action) {
+        self.retrying = false;
+        let game_action = match action {
+            Action::Stand => GameAction::Stand,
+            Action::Double => GameAction::Double,
+            Action::Split => GameAction::Split,
+            _ => GameAction::Hit,
+        };
+        self.apply(|game| game.process_player_action(game_action, 0));
+    }
+
+    /// The dealer plays one step per tick, once the previous step has finished animating
+    pub fn handle_dealer_turn(&mut self, event: &AppEvent) -> std::io::Result<ModelResponse> {
+        if *event != AppEvent::Tick {
+            return Ok(ModelResponse::NoOp);
+        }
+        self.apply(|game| game.next_dealer_turn());
+        if let GameState::DealerTurn { .. } = *self.game.get_state() {
+            // Give the player a moment to see each dealer card
+            self.animator.push(AnimationKind::Pause);
+        }
         Ok(ModelResponse::Refresh)
     }
 
-    pub fn handle_round_complete(&mut self, bankroll: f64) -> std::io::Result<ModelResponse> {
+    pub fn handle_round_complete(&mut self, bankroll: f64, event: &AppEvent) -> std::io::Result<ModelResponse> {
         self.bankroll = bankroll;
-        if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Release {
-                return Ok(ModelResponse::Refresh);
+        let AppEvent::Key(key) = event else {
+            return Ok(ModelResponse::NoOp);
+        };
+        match bindings().action(Context::RoundOver, key) {
+            Some(Action::NextRound) => Ok(self.next_round()),
+            Some(Action::LeaveTable) => {
+                Ok(ModelResponse::OpenModal(Modal::choice(
+                    LEAVE_TABLE_MODAL,
+                    "Leave the table",
+                    "What would you like to do?",
+                    LEAVE_TABLE_OPTIONS.iter().map(|o| o.to_string()).collect(),
+                )))
+            }
+            Some(Action::MainMenu) => Ok(ModelResponse::Navigate(Navigation::PopToRoot)),
+            Some(Action::Back) => Ok(ModelResponse::Navigate(Navigation::Pop)),
+            Some(Action::Quit) => Ok(ModelResponse::Exit),
+            _ => Ok(ModelResponse::NoOp),
+        }
+    }
+
+    /// Running count and true count of the cards seen since the shuffle
+    fn counts(&self, system: CountSystem) -> (i32, Option<f64>) {
+        let running_count = system.running_count(self.rules.decks, self.shoe.seen());
+        (running_count, system.true_count(running_count, self.shoe.decks_remaining()))
+    }
+
+    /// The count the ramp sizes the next bet on, and that bet. Training keeps a
+    /// Hi-Lo count when the count panel is off, count practice has no ramp so
+    /// the count stays hidden.
+    fn recommended_bet(&self) -> Option<(CountSystem, i32, f64)> {
+        let ramp = self.bet_ramp?;
+        let system = self.count_system.or(self.trainer.as_ref().map(|_| CountSystem::HiLo))?;
+        let (running_count, true_count) = self.counts(system);
+        let count = betting_count(running_count, true_count);
+        Some((system, count, ramp.bet(count)))
+    }
+
+    /// "The ramp bets $70 at a true count of +3"
+    fn bet_suggestion(&self) -> Option<String> {
+        let (system, count, bet) = self.recommended_bet()?;
+        let kind = if system.is_balanced() { "true" } else { "running" };
+        Some(format!("The ramp bets ${} at a {} count of {:+}", bet, kind, count))
+    }
+
+    /// In training mode, counts the bet about to be placed against the ramp.
+    /// Returns what was wrong with it, if anything.
+    fn grade_bet(&mut self) -> Option<String> {
+        let suggestion = self.bet_suggestion()?;
+        let (_, _, recommended) = self.recommended_bet()?;
+        let trainer = self.trainer.as_mut()?;
+        trainer.grade_bet(self.user_bet, recommended);
+        match self.user_bet - recommended {
+            over if over > 0.0 => Some(format!("Over-bet by ${}. {}", over, suggestion)),
+            under if under < 0.0 => Some(format!("Under-bet by ${}. {}", -under, suggestion)),
+            _ => None,
+        }
+    }
+
+    /// Asks for the running count, or the true count after it
+    fn count_question(&self, id: &'static str, retry: bool) -> Option<ModelResponse> {
+        let system = self.count_quiz.as_ref()?.system();
+        let question = match id {
+            TRUE_COUNT_MODAL => "And the true count?".to_string(),
+            _ => format!("What is the {} running count?", system),
+        };
+        let message = if retry { format!("That isn't a number. {}", question) } else { question };
+        Some(ModelResponse::OpenModal(Modal::text_input(id, "Count check", &message, "")))
+    }
+
+    fn grade_count(&mut self, running: i32, true_count: Option<f64>) -> ModelResponse {
+        let Some(system) = self.count_quiz.as_ref().map(CountQuiz::system) else {
+            return ModelResponse::Refresh;
+        };
+        let (actual_running, actual_true) = self.counts(system);
+        let Some(quiz) = self.count_quiz.as_mut() else {
+            return ModelResponse::Refresh;
+        };
+        match quiz.answer(running, actual_running, true_count.zip(actual_true)) {
+            Ok(feedback) => ModelResponse::Toast(feedback),
+            Err(err) => ModelResponse::Toast(format!("Count progress not saved: {}", err)),
+        }
+    }
+
+    /// The training or count practice report, when there is one
+    fn report(&self) -> Option<HelpOverlay> {
+        match (&self.trainer, &self.count_quiz) {
+            (Some(trainer), _) => Some(trainer.report_card()),
+            (None, Some(quiz)) => Some(quiz.report()),
+            (None, None) => None,
+        }
+    }
+
+    /// Clears the table, and reshuffles once the cut card came out
+    fn next_round(&mut self) -> ModelResponse {
+        self.game.next_round();
+        self.shoe.end_round();
+        if std::mem::take(&mut self.shoe_ran_out) {
+            return ModelResponse::Toast("The shoe ran out mid-round and was reshuffled".to_string());
+        }
+        if !self.shoe.needs_shuffle() {
+            return ModelResponse::Refresh;
+        }
+        self.shuffle();
+        ModelResponse::Toast("Cut card reached, the shoe is reshuffled".to_string())
+    }
+
+    /// Shuffles the shoe in front of the player and burns the first card, for show
+    fn shuffle(&mut self) {
+        self.game.shuffle_shoe();
+        self.shoe.shuffle();
+        self.shoe.burn();
+        self.animator.push(AnimationKind::Shuffle);
+        self.animator.push(AnimationKind::BurnCard);
+        self.cut_due = self.player_cuts;
+    }
+
+    fn cut_question(&self, retry: bool) -> ModelResponse {
+        let (min, max) = CUT_CARD_RANGE;
+        let question = format!("Place the cut card, {}% to {}% into the shoe", min, max);
+        let message = if retry { format!("That isn't between {} and {}. {}", min, max, question) } else { question };
+        let current = format!("{:.0}", self.rules.penetration * 100.0);
+        ModelResponse::OpenModal(Modal::text_input(CUT_CARD_MODAL, "Cut the shoe", &message, &current))
+    }
+
+    /// Where the player's keys go right now, `None` while the dealer plays
+    fn context(&self) -> Option<Context> {
+        match *self.game.get_state() {
+            GameState::WaitingForBet { .. } => Some(Context::Betting),
+            GameState::PlayerTurn { .. } => Some(Context::PlayerTurn),
+            GameState::RoundComplete { .. } => Some(Context::RoundOver),
+            GameState::WaitingToDeal { .. } | GameState::DealerTurn { .. } => None,
+        }
+    }
+
+    /// Whether `action` would do anything if pressed right now
+    fn is_legal(&self, action: Action) -> bool {
+        match (action, self.game.get_state()) {
+            (Action::PlaceBet, _) => self.user_bet > 0f64,
+            (Action::Double | Action::Split, GameState::PlayerTurn { player_hands, active_hand_index, player_bankroll, .. }) => {
+                let Some(hand) = player_hands.get(*active_hand_index) else {
+                    return false;
+                };
+                // Both put up a second bet the size of the first, on the first two cards only
+                let first_two_cards = hand.cards.len() == 2 && *player_bankroll >= self.user_bet;
+                match action {
+                    Action::Split => first_two_cards && hand.cards[0].rank == hand.cards[1].rank,
+                    _ => first_two_cards,
+                }
             }
-            match key.code {
-                KeyCode::Enter | KeyCode::Char('y') => {
-                    self.game.next_round();
-                    return Ok(ModelResponse::Refresh);
+            (Action::Hint, _) => self.hint_mode == HintMode::OnRequest && !self.hint_requested,
+            _ => true,
+        }
+    }
+
+    /// The active hand as basic strategy sees it, during the player's turn
+    fn situation(&self) -> Option<Situation> {
+        let GameState::PlayerTurn { player_hands, dealer_hand, active_hand_index, .. } = self.game.get_state() else {
+            return None;
+        };
+        let hand = player_hands.get(*active_hand_index)?;
+        let up_card = dealer_hand.cards.first()?;
+        let can_surrender = self.rules.surrender && player_hands.len() == 1 && hand.cards.len() == 2;
+        Some(Situation::new(
+            &hand.cards,
+            up_card,
+            self.is_legal(Action::Double),
+            self.is_legal(Action::Split),
+            can_surrender,
+        ))
+    }
+
+    /// The basic strategy hint, when it should be shown right now
+    fn hint(&self) -> Option<String> {
+        let shown = match self.hint_mode {
+            HintMode::Off => false,
+            HintMode::OnRequest => self.hint_requested,
+            HintMode::Always => true,
+        };
+        if !shown {
+            return None;
+        }
+        let situation = self.situation()?;
+        Some(format!("Hint: {} ({})", best_play(&situation, &self.rules), situation.describe()))
+    }
+
+    /// What the footer lists for the current state, greyed out when not legal right now
+    fn footer_hints(&self) -> Vec<FooterHint> {
+        let Some(context) = self.context() else {
+            return vec![];
+        };
+        let mut hints = vec![];
+        if context == Context::Betting {
+            hints.push(FooterHint::new("0-9", "Bet"));
+            hints.push(FooterHint::new(key_label(KeyCode::Backspace), "Erase").enabled(self.user_bet > 0f64));
+        }
+        let has_report = self.trainer.is_some() || self.count_quiz.is_some();
+        let actions = context.actions().into_iter().filter(|action| match action {
+            Action::ReportCard => has_report,
+            // What is left in the shoe would give the count away
+            Action::ShoeView => self.count_quiz.is_none(),
+            // The engine plays every hand out
+            Action::Surrender => false,
+            _ => true,
+        });
+        hints.extend(actions.map(|action| match action {
+            Action::HintMode => FooterHint::new(bindings().key_hint(action), format!("Hints: {}", self.hint_mode.label())),
+            _ => FooterHint::action(action).enabled(self.is_legal(action)),
+        }));
+        hints
+    }
+
+    pub fn handle_modal_result(&mut self, id: &str, result: &ModalResult) -> std::io::Result<ModelResponse> {
+        match (id, result) {
+            (FORFEIT_MODAL, ModalResult::Confirmed) => Ok(ModelResponse::Exit),
+            (MISTAKE_MODAL, ModalResult::Choice(1)) => {
+                // Played anyway, as long as the hand still allows it. The mistake was already scored.
+                if let Some(action) = self.pending_action.take().filter(|action| self.is_legal(*action)) {
+                    self.send(action);
                 }
-                KeyCode::Char('n') => {
-                    return Ok(ModelResponse::Exit);
+                Ok(ModelResponse::Refresh)
+            }
+            // Taken back, the game never saw it but the mistake still counts
+            (MISTAKE_MODAL, _) => {
+                self.pending_action = None;
+                self.retrying = true;
+                Ok(ModelResponse::Refresh)
+            }
+            (RUNNING_COUNT_MODAL, ModalResult::Text(answer)) => {
+                let Ok(running) = answer.trim().trim_start_matches('+').parse::<i32>() else {
+                    return Ok(self.count_question(RUNNING_COUNT_MODAL, true).unwrap_or(ModelResponse::Refresh));
+                };
+                match self.count_quiz.as_ref().map(|quiz| quiz.system().is_balanced()) {
+                    Some(true) => {
+                        self.quiz_running = Some(running);
+                        Ok(self.count_question(TRUE_COUNT_MODAL, false).unwrap_or(ModelResponse::Refresh))
+                    }
+                    _ => Ok(self.grade_count(running, None)),
                 }
-                _ => {}
             }
+            (TRUE_COUNT_MODAL, ModalResult::Text(answer)) => {
+                let Ok(true_count) = answer.trim().trim_start_matches('+').parse::<f64>() else {
+                    return Ok(self.count_question(TRUE_COUNT_MODAL, true).unwrap_or(ModelResponse::Refresh));
+                };
+                let running = self.quiz_running.take().unwrap_or_default();
+                Ok(self.grade_count(running, Some(true_count)))
+            }
+            // Skipped, nothing is graded
+            (RUNNING_COUNT_MODAL | TRUE_COUNT_MODAL, _) => {
+                self.quiz_running = None;
+                Ok(ModelResponse::Refresh)
+            }
+            (CUT_CARD_MODAL, ModalResult::Text(answer)) => {
+                let (min, max) = CUT_CARD_RANGE;
+                let Some(percent) = answer.trim().trim_end_matches('%').parse::<u32>().ok().filter(|p| (min..=max).contains(p)) else {
+                    return Ok(self.cut_question(true));
+                };
+                self.rules.penetration = percent as f64 / 100.0;
+                self.shoe.set_penetration(self.rules.penetration);
+                Ok(ModelResponse::Toast(format!("Cut card placed {}% into the shoe", percent)))
+            }
+            // Keep playing
+            (LEAVE_TABLE_MODAL, ModalResult::Choice(0)) => Ok(self.next_round()),
+            (LEAVE_TABLE_MODAL, ModalResult::Choice(1)) => Ok(ModelResponse::Navigate(Navigation::PopToRoot)),
+            (LEAVE_TABLE_MODAL, ModalResult::Choice(2)) => Ok(ModelResponse::Exit),
+            _ => Ok(ModelResponse::Refresh),
+        }
+    }
+
+    /// Plays one frame of the running animation per tick. Any key press skips
+    /// straight to the end.
+    pub fn handle_animation_frame(&mut self, event: &AppEvent) -> std::io::Result<ModelResponse> {
+        match event {
+            AppEvent::Tick => self.animator.tick(),
+            AppEvent::Key(_) => self.animator.skip(),
+            _ => return Ok(ModelResponse::NoOp),
         }
         Ok(ModelResponse::Refresh)
     }
 }
 
+/// The strategy play a player action stands for
+fn strategy_play(action: Action) -> Play {
+    match action {
+        Action::Stand => Play::Stand,
+        Action::Double => Play::Double,
+        Action::Split => Play::Split,
+        _ => Play::Hit,
+    }
+}
+
 impl Model for GameScreen {
-    fn update(&mut self) -> std::io::Result<ModelResponse> {
+    fn update(&mut self, event: &AppEvent) -> std::io::Result<ModelResponse> {
+        if let AppEvent::ModalClosed { id, result } = event {
+            return self.handle_modal_result(id, result);
+        }
+        if !self.animator.is_idle() {
+            return self.handle_animation_frame(event);
+        }
+        if let Some(problem) = self.count_quiz.as_mut().and_then(CountQuiz::take_problem) {
+            return Ok(ModelResponse::Toast(problem));
+        }
+        if self.quiz_due && matches!(*self.game.get_state(), GameState::RoundComplete { .. }) {
+            self.quiz_due = false;
+            return Ok(self.count_question(RUNNING_COUNT_MODAL, false).unwrap_or(ModelResponse::Refresh));
+        }
+        if self.cut_due && matches!(*self.game.get_state(), GameState::WaitingForBet { .. }) {
+            self.cut_due = false;
+            return Ok(self.cut_question(false));
+        }
+        if let (Some(context), AppEvent::Key(key)) = (self.context(), event) {
+            match bindings().action(context, key) {
+                Some(Action::ReportCard) => {
+                    if let Some(report) = self.report() {
+                        return Ok(ModelResponse::OpenPage(report));
+                    }
+                }
+                Some(Action::ShoeView) if self.count_quiz.is_none() => {
+                    return Ok(ModelResponse::OpenPage(self.shoe.report()));
+                }
+                _ => {}
+            }
+        }
+
         let g_state = (*self.game.get_state()).clone();
         match g_state {
             GameState::WaitingForBet { player_bankroll } => {
-                self.handle_waiting_for_bet(player_bankroll)
+                self.handle_waiting_for_bet(player_bankroll, event)
             },
-            GameState::WaitingToDeal {player_bet, player_bankroll} => {
-                self.handle_waiting_to_deal(player_bet, player_bankroll)
+            GameState::WaitingToDeal { .. } => {
+                self.handle_waiting_to_deal()
             },
             GameState::PlayerTurn {..} => {
-                self.handle_player_turn()
+                self.handle_player_turn(event)
             }
             GameState::DealerTurn {..} => {
-                sleep(Duration::from_millis(500));
-                self.handle_dealer_turn()
+                self.handle_dealer_turn(event)
             },
             GameState::RoundComplete {player_bankroll, ..} => {
-                self.handle_round_complete(player_bankroll)
+                self.handle_round_complete(player_bankroll, event)
             }
         }
     }
 
     fn ui(&mut self, frame: &mut Frame) {
         let screen = frame.area();
-        let screen_layout = Self::create_main_layout(screen);
+        let hints = self.footer_hints();
+        // The footer sits between a one column margin on either side
+        let footer_height = footer_lines(&hints, screen.width.saturating_sub(2), MAX_FOOTER_LINES).len().max(1);
+        let screen_layout = Self::create_main_layout(screen, footer_height as u16);
+        frame.render_widget(Block::default().style(theme().felt()), screen);
 
-        let (dealer_horizontal, dealer_vertical) = Self::create_dealer_section(screen_layout[1]);
-        self.render_dealer_section(frame, dealer_horizontal, dealer_vertical);
+        let compact = Self::is_compact(screen);
+        let (dealer_horizontal, dealer_vertical) = Self::create_dealer_section(screen_layout[1], compact);
+        self.render_dealer_section(frame, dealer_horizontal.clone(), dealer_vertical.clone());
 
-        let (player_horizontal, player_vertical) = Self::create_player_section(screen_layout[2]);
-        self.render_player_section(frame, player_horizontal, player_vertical);
+        let (player_horizontal, player_vertical) = Self::create_player_section(screen_layout[2], compact);
+        self.render_player_section(frame, player_horizontal.clone(), player_vertical.clone());
+        self.render_controls(frame, player_horizontal[1], player_vertical.clone());
 
-        let stats_section = Self::create_stats_section(screen_layout[3]);
-        self.render_stats_section(frame, stats_section);
+        self.render_stats_section(frame, screen_layout[3]);
 
         let footer = Self::create_footer_section(screen_layout[4]);
-        render_footer_spans(frame, vec![], footer[1]);
+        render_footer_hints(frame, &hints, footer[1]);
+
+        self.render_animation(frame, dealer_horizontal, dealer_vertical, player_horizontal, player_vertical);
+    }
+
+    fn help(&self) -> HelpOverlay {
+        HelpOverlay::new(self.context().unwrap_or(Context::PlayerTurn), &self.rules)
+    }
+
+    fn resumable(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::layout::Rect;
+    use crate::testing::{Harness, ScriptedEvents};
+    use super::GameScreen;
+
+    fn open_table() -> ScriptedEvents {
+        // "Play" is the first main menu entry, then accept the default player name
+        ScriptedEvents::new().key(KeyCode::Enter).key(KeyCode::Enter)
+    }
+
+    fn hi_lo_count_panel() -> ScriptedEvents {
+        // The count panel is the eighth setting
+        ScriptedEvents::new().type_text("s").type_text("jjjjjjj").key(KeyCode::Right)
+    }
+
+    #[test]
+    fn typed_bet_is_shown() {
+        let mut harness = Harness::new();
+        harness.run(open_table().type_text("25"));
+        harness.assert_contains("Place your bet");
+        harness.assert_contains("BET: $25");
+    }
+
+    #[test]
+    fn bet_hit_stand_completes_the_round() {
+        let mut harness = Harness::new();
+        harness.run(
+            open_table()
+                .type_text("25")
+                .key(KeyCode::Enter)
+                .ticks(1)
+                .type_text("hs")
+                .ticks(20),
+        );
+        harness.assert_contains("All done");
+        harness.assert_contains("Bet: $25");
+    }
+
+    #[test]
+    fn strategy_hint_is_shown_on_request() {
+        let mut harness = Harness::new();
+        let deal = open_table().type_text("25").key(KeyCode::Enter).ticks(1);
+        harness.run(deal.type_text("b"));
+        harness.assert_not_contains("Hint: ");
+
+        let deal = open_table().type_text("25").key(KeyCode::Enter).ticks(1);
+        harness.run(deal.type_text("vb"));
+        harness.assert_contains("Hints: On Request");
+        harness.assert_contains("Hint: ");
+    }
+
+    #[test]
+    fn training_grades_each_decision() {
+        let mut harness = Harness::new();
+        // Standing on a 4 and a queen against a 7 is a mistake, hitting is right. The $10
+        // bet is on the ramp, so the only miss on the board is the hard hand's.
+        let stand = || ScriptedEvents::new().type_text("t10").key(KeyCode::Enter).ticks(1).type_text("s");
+        harness.run(stand());
+        harness.assert_contains("Basic strategy says Hit");
+        harness.assert_contains("Take it back");
+        harness.assert_contains("0/1 (0%)");
+
+        harness.run(stand().key(KeyCode::Down).key(KeyCode::Enter).ticks(20));
+        harness.assert_contains("All done");
+        harness.assert_contains("0/1 (0%)");
+    }
+
+    #[test]
+    fn a_mistake_taken_back_still_counts() {
+        let mut harness = Harness::new();
+        // The hit that replaces the stand is graded, but only the stand is scored
+        harness.run(ScriptedEvents::new().type_text("t10").key(KeyCode::Enter).ticks(1).type_text("s").key(KeyCode::Enter).type_text("h"));
+        harness.assert_not_contains("Take it back");
+        harness.assert_contains("0/1 (0%)");
+    }
+
+    #[test]
+    fn training_has_a_report_card() {
+        let mut harness = Harness::new();
+        harness.run(ScriptedEvents::new().type_text("t25").key(KeyCode::Enter).ticks(1).type_text("r"));
+        harness.assert_contains("Report card");
+        harness.assert_contains("Grade: -");
+    }
+
+    #[test]
+    fn training_grades_the_bet_against_the_ramp() {
+        let mut harness = Harness::new();
+        // A fresh shoe is at a true count of +0, one $10 unit on the default spread
+        harness.run(ScriptedEvents::new().type_text("t"));
+        harness.assert_contains("The ramp bets $10 at a true count of +0");
+        harness.run(ScriptedEvents::new().type_text("t25").key(KeyCode::Enter));
+        harness.assert_contains("Over-bet by $15");
+        harness.assert_contains(" Bets ");
+        harness.assert_contains("0/1 (0%)");
+    }
+
+    #[test]
+    fn ramp_suggestions_follow_the_count_panel() {
+        let mut harness = Harness::new();
+        harness.run(open_table());
+        harness.assert_not_contains("The ramp bets");
+        harness.run(hi_lo_count_panel());
+        harness.run(open_table());
+        harness.assert_contains("The ramp bets $10 at a true count of +0");
+    }
+
+    #[test]
+    fn count_panel_tracks_the_shoe() {
+        let mut harness = Harness::new();
+        harness.run(hi_lo_count_panel());
+        harness.assert_contains("Count Panel: < Hi-Lo >");
+        harness.run(open_table().type_text("25").key(KeyCode::Enter).ticks(1));
+        harness.assert_contains(" Hi-Lo ");
+        harness.assert_contains("RC ");
+        harness.assert_contains(" True count ");
+        // Three cards out of six decks still rounds to a full shoe
+        harness.assert_contains("6.0");
+    }
+
+    #[test]
+    fn shoe_report_shows_what_is_left() {
+        let mut harness = Harness::new();
+        harness.run(open_table().type_text("25").key(KeyCode::Enter).ticks(1).type_text("x"));
+        harness.assert_contains(" Shoe ");
+        harness.assert_contains("of 312 cards dealt");
+        harness.assert_contains("the cut card is at 234 (75%)");
+        // The burn card is only for show, it never leaves the shoe
+        harness.assert_contains("Discard tray: 0 cards");
+        harness.assert_contains("Burned: 1 for show");
+        harness.assert_contains("Reshuffled: 0 since the table opened");
+        harness.assert_contains("Left in the shoe");
+    }
+
+    #[test]
+    fn the_player_can_place_the_cut_card() {
+        let mut harness = Harness::new();
+        // The cut card is the third setting from the end, before the flash drill's
+        harness.run(ScriptedEvents::new().type_text("s").key(KeyCode::Up).key(KeyCode::Up).key(KeyCode::Up).key(KeyCode::Right));
+        harness.assert_contains("Cut Card: < Player >");
+        // Asked on the first tick at the table
+        let cut = || open_table().ticks(1).key(KeyCode::Backspace).key(KeyCode::Backspace);
+        harness.run(cut().type_text("40").key(KeyCode::Enter));
+        harness.assert_contains("That isn't between 50 and 90");
+        harness.run(cut().type_text("60").key(KeyCode::Enter).type_text("?"));
+        harness.assert_contains("Cut card placed 60% into the shoe");
+        harness.assert_contains("Reshuffled after 60%");
+    }
+
+    #[test]
+    fn count_practice_keeps_the_count_hidden() {
+        let mut harness = Harness::new();
+        harness.run(hi_lo_count_panel());
+        harness.run(ScriptedEvents::new().type_text("o25").key(KeyCode::Enter).ticks(1));
+        harness.assert_contains(" Running count ");
+        harness.assert_not_contains("RC ");
+        harness.assert_not_contains("Decks left");
+        harness.run(ScriptedEvents::new().type_text("o25").key(KeyCode::Enter).ticks(1).type_text("x"));
+        harness.assert_not_contains("Left in the shoe");
+    }
+
+    #[test]
+    fn quitting_mid_hand_asks_first() {
+        let mut harness = Harness::new();
+        harness.run(open_table().type_text("10").key(KeyCode::Enter).ticks(1).type_text("q"));
+        harness.assert_contains("forfeits the hand in play");
+        harness.assert_contains(" Jack ");
+    }
+
+    #[test]
+    fn cancelling_the_quit_dialog_returns_to_the_table() {
+        let mut harness = Harness::new();
+        harness.run(open_table().type_text("10").key(KeyCode::Enter).ticks(1).type_text("q").key(KeyCode::Esc));
+        harness.assert_not_contains("forfeits the hand in play");
+        harness.assert_contains(" Jack ");
+    }
+
+    #[test]
+    fn empty_bet_is_refused_with_a_toast() {
+        let mut harness = Harness::new();
+        harness.run(open_table().key(KeyCode::Enter));
+        harness.assert_contains("Type in a bet first");
+        harness.assert_contains("Place your bet");
+    }
+
+    #[test]
+    fn footer_shows_the_bound_keys() {
+        let mut harness = Harness::new();
+        harness.run(open_table());
+        harness.assert_contains(" Enter  Place Bet ");
+        harness.assert_contains(" Q  Quit ");
+    }
+
+    #[test]
+    fn help_lists_the_betting_keys_and_house_rules() {
+        let mut harness = Harness::new();
+        harness.run(open_table().type_text("?"));
+        harness.assert_contains("0-9: Type in the bet");
+        harness.assert_contains("6 deck shoe");
+        harness.assert_contains("Blackjack: 3 to 2");
+    }
+
+    #[test]
+    fn closing_the_help_keeps_the_bet() {
+        let mut harness = Harness::new();
+        harness.run(open_table().type_text("2?").key(KeyCode::Esc).type_text("5"));
+        harness.assert_not_contains("House rules");
+        harness.assert_contains("BET: $25");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn footer_wraps_on_narrow_terminals() {
+        let mut harness = Harness::with_size(60, 30);
+        harness.run(open_table());
+        harness.assert_contains(" 0-9  Bet ");
+        harness.assert_contains(" Q  Quit ");
+    }
+
+    #[test]
+    fn table_fits_a_standard_terminal() {
+        let mut harness = Harness::with_size(80, 24);
+        harness.run(open_table().type_text("10").key(KeyCode::Enter).ticks(1));
+        harness.assert_contains("Dealer McGee");
+        harness.assert_contains(" Jack ");
+        harness.assert_contains("Cards");
+    }
+
+    #[test]
+    fn chips_and_the_scroll_wheel_change_the_bet() {
+        let mut harness = Harness::new();
+        harness.run(open_table());
+        let chip = harness.position_of("$25").unwrap();
+        harness.run(open_table().click(chip).click(chip).scroll(2).scroll(-1));
+        harness.assert_contains("BET: $55");
+    }
+
+    #[test]
+    fn action_buttons_can_be_clicked() {
+        let mut harness = Harness::new();
+        let deal = || open_table().type_text("10").key(KeyCode::Enter).ticks(1);
+        harness.run(deal());
+        let hit = harness.position_of("H Hit").unwrap();
+        harness.run(deal().click(hit));
+        harness.assert_contains("FourHearts QueenDiamonds ");
+        harness.assert_not_contains("FourHearts QueenDiamonds <");
+
+        let stand = harness.position_of("S Stand").unwrap();
+        harness.run(deal().click(stand).ticks(20));
+        harness.assert_contains("All done");
+    }
+
+    #[test]
+    fn split_and_double_are_only_sent_when_allowed() {
+        let mut harness = Harness::new();
+        // The first hand is a 4 and a queen, no pair to split
+        harness.run(open_table().type_text("25").key(KeyCode::Enter).ticks(1).type_text("p"));
+        harness.assert_contains("Can't split this hand");
+        harness.assert_contains("FourHearts QueenDiamonds <");
+
+        // After a hit the hand can't be doubled, and the greyed out button can't be clicked
+        harness.run(open_table().type_text("25").key(KeyCode::Enter).ticks(1).type_text("h"));
+        let double = harness.position_of("D Double").unwrap();
+        harness.run(open_table().type_text("25").key(KeyCode::Enter).ticks(1).type_text("h").click(double));
+        harness.assert_contains("Bet: $25");
+        harness.assert_not_contains("Bet: $50");
+    }
+
+    #[test]
+    fn every_split_hand_has_a_place() {
+        let rows = GameScreen::create_default_playable_section(Rect::new(0, 0, 100, 20), false).1;
+        assert_eq!(GameScreen::hand_rect(&rows, 1), rows[2]);
+        for i in 2..8 {
+            let rect = GameScreen::hand_rect(&rows, i);
+            assert!(rect.y > rows[2].y && rect.bottom() < rows[2].bottom(), "{:?}", rect);
+        }
+    }
+}
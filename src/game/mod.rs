@@ -1 +1,11 @@
-pub mod game_screen;
\ No newline at end of file
+pub mod game_screen;
+pub mod animation;
+pub mod rules;
+pub mod strategy;
+pub mod trainer;
+pub mod count;
+pub mod count_quiz;
+pub mod deviations;
+pub mod shoe;
+pub mod bet_ramp;
+pub mod simulation;
@@ -0,0 +1,83 @@
+use blackjack_engine::hand::{Hand, HandOutcome};
+
+/// The rules a table is played with. Only the number of decks can be picked
+/// for now, the rest is how the engine plays every hand.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HouseRules {
+    pub decks: u8,
+    /// H17 when true, S17 when false
+    pub dealer_hits_soft_17: bool,
+    /// DAS, doubling a hand made by splitting
+    pub double_after_split: bool,
+    /// Giving up half the bet instead of playing the hand out
+    pub surrender: bool,
+    /// Share of the shoe dealt before the cut card comes out and it is reshuffled
+    pub penetration: f64,
+}
+
+impl Default for HouseRules {
+    fn default() -> HouseRules {
+        HouseRules {
+            decks: 6,
+            dealer_hits_soft_17: false,
+            double_after_split: true,
+            surrender: false,
+            penetration: 0.75,
+        }
+    }
+}
+
+impl HouseRules {
+    /// One line per rule, as shown in the help overlay
+    pub fn describe(&self) -> Vec<String> {
+        vec![
+            format!("{} deck shoe", self.decks),
+            format!("Reshuffled after {:.0}% of the shoe is dealt", self.penetration * 100.0),
+            if self.dealer_hits_soft_17 { "Dealer hits soft 17 (H17)" } else { "Dealer stands on all 17s (S17)" }.to_string(),
+            "Double down on any first two cards".to_string(),
+            if self.double_after_split { "Double after split allowed (DAS)" } else { "No double after split" }.to_string(),
+            "Split any two cards of the same rank".to_string(),
+            if self.surrender { "Late surrender, no insurance" } else { "No surrender, no insurance" }.to_string(),
+        ]
+    }
+
+    /// What each outcome pays, per unit bet
+    pub fn payouts(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("Blackjack", "3 to 2"),
+            ("Win", "1 to 1"),
+            ("Push", "Bet returned"),
+            ("Loss or bust", "Bet lost"),
+        ]
+    }
+}
+
+/// What the settled `hands` won or lost all together, doubles and splits included
+pub fn net_result(hands: &[Hand]) -> f64 {
+    hands
+        .iter()
+        .map(|hand| {
+            let paid = match hand.outcome {
+                Some(HandOutcome::Blackjack) => 2.5,
+                Some(HandOutcome::Win) => 2.0,
+                Some(HandOutcome::Push) => 1.0,
+                Some(HandOutcome::Loss) | None => 0.0,
+            };
+            hand.bet * paid - hand.bet
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use blackjack_engine::hand::{Hand, HandOutcome};
+    use super::net_result;
+
+    #[test]
+    fn doubles_and_splits_are_settled_at_their_own_bets() {
+        let hand = |bet, outcome| Hand { bet, outcome: Some(outcome), ..Hand::new() };
+        // A doubled hand won, the other half of the split lost
+        assert_eq!(net_result(&[hand(20.0, HandOutcome::Win), hand(10.0, HandOutcome::Loss)]), 10.0);
+        assert_eq!(net_result(&[hand(10.0, HandOutcome::Blackjack), hand(10.0, HandOutcome::Push)]), 15.0);
+    }
+}
@@ -0,0 +1,275 @@
+use blackjack_engine::card::{Card, Rank, Suit};
+use ratatui::prelude::{Line, Span, Stylize};
+use crate::overlay::help::HelpOverlay;
+use crate::theme::theme;
+
+pub const CARDS_PER_DECK: usize = 52;
+
+/// Characters across the penetration bar of the shoe report
+const BAR_WIDTH: usize = 50;
+
+const RANKS: [Rank; 13] = [
+    Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven, Rank::Eight,
+    Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King, Rank::Ace,
+];
+const SUITS: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+/// Every card of `decks` decks, in order
+pub fn shoe_cards(decks: u8) -> Vec<Card> {
+    (0..decks)
+        .flat_map(|_| SUITS)
+        .flat_map(|suit| RANKS.map(move |rank| Card { rank, suit }))
+        .collect()
+}
+
+/// Keeps track of the cards that came out of the shoe since it was last
+/// shuffled. The engine doesn't say, so the table watches the cards as they
+/// are turned face up and reshuffles once the cut card is reached. The engine
+/// can't burn a card, so the burn card is only for show. It is never taken
+/// out of the shoe, and it is counted like any other card once it is dealt.
+#[derive(Clone, Debug)]
+pub struct ShoeTracker {
+    decks: u8,
+    /// Share of the shoe dealt before it is reshuffled
+    penetration: f64,
+    /// Every card seen since the shuffle, in the order they showed up
+    seen: Vec<Card>,
+    /// Cards seen in the current round, to tell new cards from ones already counted
+    round: Vec<Card>,
+    /// Cards burned since the shuffle, shown in the report but not taken off the shoe
+    burned: usize,
+    /// Times the shoe was reshuffled since the table opened
+    shuffles: usize,
+}
+
+impl ShoeTracker {
+    pub fn new(decks: u8, penetration: f64) -> ShoeTracker {
+        ShoeTracker {
+            decks,
+            penetration,
+            seen: vec![],
+            round: vec![],
+            burned: 0,
+            shuffles: 0,
+        }
+    }
+
+    /// Takes every card face up on the table right now, and remembers the ones not seen before
+    pub fn observe(&mut self, visible: &[Card]) {
+        let mut known = self.round.clone();
+        for card in visible {
+            match known.iter().position(|known| known == card) {
+                Some(index) => {
+                    known.swap_remove(index);
+                }
+                None => {
+                    self.round.push(card.clone());
+                    self.seen.push(card.clone());
+                }
+            }
+        }
+    }
+
+    /// The table was cleared, the next cards seen are new ones
+    pub fn end_round(&mut self) {
+        self.round.clear();
+    }
+
+    pub fn shuffle(&mut self) {
+        self.seen.clear();
+        self.round.clear();
+        self.burned = 0;
+        self.shuffles += 1;
+    }
+
+    /// The first card after a shuffle goes to the tray unseen, for show only
+    pub fn burn(&mut self) {
+        self.burned += 1;
+    }
+
+    /// The engine refills an empty shoe on its own, in the middle of a round
+    /// if it has to. Cards seen past the end of the shoe came from the new
+    /// one, so the count starts over with them. Returns whether that happened.
+    pub fn refill_if_emptied(&mut self) -> bool {
+        let total = self.total_cards();
+        if self.seen.len() <= total {
+            return false;
+        }
+        self.seen.drain(..total);
+        self.burned = 0;
+        self.shuffles += 1;
+        true
+    }
+
+    /// Moves the cut card, e.g. when the player places it
+    pub fn set_penetration(&mut self, penetration: f64) {
+        self.penetration = penetration;
+    }
+
+    /// Cards out of the shoe
+    pub fn dealt(&self) -> usize {
+        self.seen.len()
+    }
+
+    pub fn seen(&self) -> &[Card] {
+        &self.seen
+    }
+
+    pub fn total_cards(&self) -> usize {
+        self.decks as usize * CARDS_PER_DECK
+    }
+
+    pub fn cards_remaining(&self) -> usize {
+        self.total_cards().saturating_sub(self.dealt())
+    }
+
+    /// Decks left to deal, to the nearest half deck the way a counter eyeballs the shoe
+    pub fn decks_remaining(&self) -> f64 {
+        let decks = self.cards_remaining() as f64 / CARDS_PER_DECK as f64;
+        ((decks * 2.0).round() / 2.0).max(0.5)
+    }
+
+    /// Cards from earlier rounds, stacked in the discard tray
+    pub fn discarded(&self) -> usize {
+        self.dealt() - self.round.len()
+    }
+
+    /// How many cards are dealt before the cut card comes out
+    pub fn cut_card(&self) -> usize {
+        (self.total_cards() as f64 * self.penetration).ceil() as usize
+    }
+
+    /// Cards of each rank still in the shoe, out of how many it started with.
+    /// A face down card counts as still in the shoe until it is turned.
+    pub fn remaining_by_rank(&self) -> Vec<(Rank, usize, usize)> {
+        let per_rank = self.decks as usize * SUITS.len();
+        RANKS
+            .iter()
+            .map(|rank| {
+                let seen = self.seen.iter().filter(|card| card.rank == *rank).count();
+                (*rank, per_rank.saturating_sub(seen), per_rank)
+            })
+            .collect()
+    }
+
+    /// How far the shoe is dealt towards the cut card, the discard tray, and
+    /// what is left in the shoe by rank
+    pub fn report(&self) -> HelpOverlay {
+        let total = self.total_cards();
+        let dealt = self.dealt();
+        let decks = |cards: usize| cards as f64 / CARDS_PER_DECK as f64;
+        let mut lines = vec![Line::from("Penetration").bold().underlined()];
+        lines.push(Line::from(format!(
+            "  {} of {} cards dealt ({:.0}%), the cut card is at {} ({:.0}%)",
+            dealt,
+            total,
+            dealt as f64 * 100.0 / total.max(1) as f64,
+            self.cut_card(),
+            self.penetration * 100.0,
+        )));
+        lines.push(penetration_bar(dealt, self.cut_card(), total));
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("  Cards left: {}, {:.1} decks", self.cards_remaining(), decks(self.cards_remaining()))));
+        lines.push(Line::from(format!("  Discard tray: {} cards, {:.1} decks high", self.discarded(), decks(self.discarded()))));
+        lines.push(Line::from(format!("  Burned: {} for show, the engine deals it later on", self.burned)));
+        lines.push(Line::from(format!("  Reshuffled: {} since the table opened", self.shuffles)));
+        lines.push(Line::from(format!("  On the table: {} cards", self.round.len())));
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("Left in the shoe").bold().underlined());
+        for (rank, left, start) in self.remaining_by_rank() {
+            let width = (left * BAR_WIDTH / 2).div_ceil(start.max(1));
+            lines.push(Line::from(format!("  {:>2}  {:>3}/{:<3} {}", rank_label(rank), left, start, "█".repeat(width))));
+        }
+        HelpOverlay::page("Shoe", lines)
+    }
+
+    /// Whether the cut card came out, checked between rounds
+    pub fn needs_shuffle(&self) -> bool {
+        self.dealt() >= self.cut_card()
+    }
+}
+
+/// "2" to "10", then "J", "Q", "K" and "A"
+fn rank_label(rank: Rank) -> String {
+    match RANKS.iter().position(|known| *known == rank) {
+        Some(index @ 0..=8) => (index + 2).to_string(),
+        _ => format!("{:?}", rank)[..1].to_string(),
+    }
+}
+
+/// `[█████░░░░│░░░]`, the dealt part of the shoe filled in and the cut card marked
+fn penetration_bar(dealt: usize, cut_card: usize, total: usize) -> Line<'static> {
+    let total = total.max(1);
+    let filled = (dealt * BAR_WIDTH / total).min(BAR_WIDTH);
+    let cut = (cut_card * BAR_WIDTH / total).min(BAR_WIDTH - 1);
+    let mut spans = vec![Span::raw("  [")];
+    for at in 0..BAR_WIDTH {
+        spans.push(match at {
+            _ if at == cut => Span::styled("│", theme().highlight()),
+            _ if at < filled => Span::raw("█"),
+            _ => Span::raw("░"),
+        });
+    }
+    spans.push(Span::raw("]"));
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use blackjack_engine::card::{Card, Rank, Suit};
+    use super::{rank_label, shoe_cards, ShoeTracker};
+
+    #[test]
+    fn cards_are_only_counted_once() {
+        let ace = Card { rank: Rank::Ace, suit: Suit::Spades };
+        let king = Card { rank: Rank::King, suit: Suit::Hearts };
+        let mut shoe = ShoeTracker::new(1, 0.75);
+        shoe.observe(std::slice::from_ref(&ace));
+        shoe.observe(&[ace.clone(), king.clone()]);
+        assert_eq!(shoe.seen().len(), 2);
+
+        // The same card in a later round is another card from the shoe
+        shoe.end_round();
+        shoe.observe(&[ace]);
+        assert_eq!(shoe.cards_remaining(), 49);
+        assert_eq!(shoe.decks_remaining(), 1.0);
+        shoe.shuffle();
+        assert!(shoe.seen().is_empty());
+    }
+
+    #[test]
+    fn a_shoe_emptied_mid_round_starts_over() {
+        let mut shoe = ShoeTracker::new(1, 0.9);
+        let deck = shoe_cards(1);
+        shoe.observe(&deck[..50]);
+        shoe.end_round();
+        assert!(!shoe.refill_if_emptied());
+        // The last round ran four cards past the end of the deck
+        shoe.observe(&deck[..6]);
+        assert!(shoe.refill_if_emptied());
+        assert_eq!((shoe.dealt(), shoe.cards_remaining()), (4, 48));
+        assert!(!shoe.needs_shuffle());
+    }
+
+    #[test]
+    fn the_shoe_is_split_between_the_tray_the_table_and_what_is_left() {
+        let card = |rank| Card { rank, suit: Suit::Clubs };
+        let mut shoe = ShoeTracker::new(2, 0.75);
+        assert_eq!(shoe.cut_card(), 78);
+        shoe.observe(&[card(Rank::Ace), card(Rank::Ten)]);
+        shoe.end_round();
+        shoe.observe(&[card(Rank::Ace)]);
+        assert_eq!((shoe.discarded(), shoe.cards_remaining()), (2, 101));
+        // The burn card never leaves the engine's shoe
+        shoe.burn();
+        assert_eq!((shoe.discarded(), shoe.cards_remaining()), (2, 101));
+
+        let remaining = shoe.remaining_by_rank();
+        assert_eq!(remaining.len(), 13);
+        assert!(remaining.contains(&(Rank::Ace, 6, 8)));
+        assert!(remaining.contains(&(Rank::Ten, 7, 8)));
+        assert!(remaining.contains(&(Rank::Two, 8, 8)));
+        assert_eq!([Rank::Two, Rank::Ten, Rank::Queen, Rank::Ace].map(rank_label), ["2", "10", "Q", "A"]);
+    }
+}
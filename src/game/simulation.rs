@@ -0,0 +1,403 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write;
+use blackjack_engine::card::Card;
+use blackjack_engine::game::{Game, GameAction, GameState};
+use blackjack_engine::game_settings::GameSettings;
+use blackjack_engine::hand::{Hand, HandOutcome};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use crate::game::bet_ramp::{betting_count, BetRamp};
+use crate::game::count::CountSystem;
+use crate::game::deviations::{Deviation, DeviationSet, DeviationTable};
+use crate::game::rules::{net_result, HouseRules};
+use crate::game::shoe::ShoeTracker;
+use crate::game::strategy::{best_play, hand_total, Play, Situation};
+use crate::settings::config::Settings;
+
+/// How the simulated player plays each hand
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum PlayerStrategy {
+    /// Basic strategy for the house rules
+    Basic,
+    /// Basic strategy plus the Illustrious 18, with the indices of the index table
+    Deviations,
+    /// Hits below 17 like the dealer, never doubles or splits
+    MimicDealer,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
+/// The simulate subcommand. Anything left out comes from the saved settings,
+/// so the numbers are for the same table the game deals.
+#[derive(Args, Debug)]
+pub struct SimulateArgs {
+    /// Rounds to play
+    #[arg(long, default_value_t = 1_000_000)]
+    pub rounds: u64,
+    #[arg(long, value_enum, default_value_t = PlayerStrategy::Basic)]
+    pub strategy: PlayerStrategy,
+    /// Decks in the shoe
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=8))]
+    pub decks: Option<u8>,
+    /// How far into the shoe the cut card goes, in percent
+    #[arg(long, value_parser = clap::value_parser!(u32).range(50..=90))]
+    pub penetration: Option<u32>,
+    /// Units bet at the top of the bet ramp, 1 bets flat
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=20))]
+    pub spread: Option<u32>,
+    /// Dollars in one betting unit
+    #[arg(long)]
+    pub unit: Option<f64>,
+    /// Counting system the bets and index plays follow
+    #[arg(long, value_enum)]
+    pub count: Option<CountSystem>,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+    pub format: OutputFormat,
+}
+
+/// A table set up to be played without a screen
+pub struct Simulation {
+    rules: HouseRules,
+    strategy: PlayerStrategy,
+    ramp: BetRamp,
+    system: CountSystem,
+    /// Index plays the engine can play, surrenders are left out
+    deviations: Vec<Deviation>,
+}
+
+/// Round results in initial bets, the number of rounds that ended that way
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct OutcomeBucket {
+    pub result: f64,
+    pub rounds: u64,
+}
+
+/// What came of a simulation. Money is in dollars, rates are shares of all hands.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SimulationReport {
+    pub rounds: u64,
+    /// Rounds plus the extra hands made by splitting
+    pub hands: u64,
+    /// Shuffles at the cut card, plus the ones forced by running out of cards
+    pub shuffles: u64,
+    /// Rounds that emptied the shoe before the cut card came out. The engine
+    /// refills it on its own, that counts as a shuffle.
+    pub shoe_ran_out: u64,
+    /// The first bet of every round, added up
+    pub initial_bets: f64,
+    /// Every bet including doubles and splits
+    pub wagered: f64,
+    pub net: f64,
+    /// Net result of an average round, in dollars
+    pub ev_per_hand: f64,
+    /// The player's loss per initial bet, as a percentage
+    pub house_edge: f64,
+    /// Spread of the round results, in initial bets
+    pub std_dev: f64,
+    pub win_rate: f64,
+    pub blackjack_rate: f64,
+    pub push_rate: f64,
+    pub loss_rate: f64,
+    pub outcomes: Vec<OutcomeBucket>,
+}
+
+/// Adds up round results as they come in, the variance the Welford way
+#[derive(Default)]
+struct Tally {
+    report: SimulationReport,
+    mean: f64,
+    sum_of_squares: f64,
+    /// By half initial bets
+    outcomes: BTreeMap<i64, u64>,
+    wins: u64,
+    blackjacks: u64,
+    pushes: u64,
+}
+
+impl Tally {
+    fn add_round(&mut self, initial_bet: f64, hands: &[Hand]) {
+        let net = net_result(hands);
+        for hand in hands {
+            self.report.wagered += hand.bet;
+            match hand.outcome {
+                Some(HandOutcome::Blackjack) => self.blackjacks += 1,
+                Some(HandOutcome::Win) => self.wins += 1,
+                Some(HandOutcome::Push) => self.pushes += 1,
+                _ => {}
+            }
+        }
+        self.report.rounds += 1;
+        self.report.hands += hands.len() as u64;
+        self.report.initial_bets += initial_bet;
+        self.report.net += net;
+
+        let result = net / initial_bet;
+        let delta = result - self.mean;
+        self.mean += delta / self.report.rounds as f64;
+        self.sum_of_squares += delta * (result - self.mean);
+        *self.outcomes.entry((result * 2.0).round() as i64).or_default() += 1;
+    }
+
+    fn finish(mut self) -> SimulationReport {
+        let rounds = self.report.rounds.max(1) as f64;
+        let hands = self.report.hands.max(1) as f64;
+        let losses = self.report.hands - self.wins - self.blackjacks - self.pushes;
+        let report = &mut self.report;
+        report.ev_per_hand = report.net / rounds;
+        report.house_edge = match report.initial_bets {
+            bets if bets > 0.0 => -report.net * 100.0 / bets,
+            _ => 0.0,
+        };
+        report.std_dev = (self.sum_of_squares / rounds).sqrt();
+        report.win_rate = self.wins as f64 / hands;
+        report.blackjack_rate = self.blackjacks as f64 / hands;
+        report.push_rate = self.pushes as f64 / hands;
+        report.loss_rate = losses as f64 / hands;
+        report.outcomes = self
+            .outcomes
+            .into_iter()
+            .map(|(halves, rounds)| OutcomeBucket { result: halves as f64 / 2.0, rounds })
+            .collect();
+        self.report
+    }
+}
+
+impl Simulation {
+    /// The table from `settings`, with anything given in `args` in its place.
+    /// Index plays need a true count, so they can't follow an unbalanced count.
+    pub fn new(args: &SimulateArgs, settings: &Settings) -> Result<Simulation, String> {
+        let mut rules = HouseRules { decks: args.decks.unwrap_or(settings.decks()), ..HouseRules::default() };
+        if let Some(penetration) = args.penetration {
+            rules.penetration = penetration as f64 / 100.0;
+        }
+        let saved = settings.bet_ramp().unwrap_or(BetRamp { unit: 10.0, spread: 1 });
+        let ramp = BetRamp {
+            unit: args.unit.filter(|unit| *unit > 0.0).unwrap_or(saved.unit),
+            spread: args.spread.unwrap_or(saved.spread),
+        };
+        let system = args.count.or(settings.count_system()).unwrap_or(CountSystem::HiLo);
+        if args.strategy == PlayerStrategy::Deviations && !system.is_balanced() {
+            return Err(format!("The index plays are true count indices and {} has no true count, pick another --count", system));
+        }
+        let (table, _) = DeviationTable::load(settings.storage_dir());
+        let deviations = table.entries().into_iter().filter(|deviation| deviation.set == DeviationSet::Illustrious18).collect();
+        Ok(Simulation { rules, strategy: args.strategy, ramp, system, deviations })
+    }
+
+    /// The running count, and the true count floored the way bets and index plays use it
+    fn count(&self, shoe: &ShoeTracker) -> i32 {
+        let running_count = self.system.running_count(self.rules.decks, shoe.seen());
+        betting_count(running_count, self.system.true_count(running_count, shoe.decks_remaining()))
+    }
+
+    /// Counts the cards on the table, and a shuffle when they ran past the end of the shoe
+    fn observe(shoe: &mut ShoeTracker, cards: &[Card], report: &mut SimulationReport) {
+        shoe.observe(cards);
+        if shoe.refill_if_emptied() {
+            report.shoe_ran_out += 1;
+            report.shuffles += 1;
+        }
+    }
+
+    /// Plays `rounds` rounds against the engine, reshuffling at the cut card like the table does
+    pub fn run(&self, rounds: u64) -> SimulationReport {
+        let mut game = Game::new(GameSettings::new("Simulation".to_string(), self.rules.decks));
+        let mut shoe = ShoeTracker::new(self.rules.decks, self.rules.penetration);
+        game.shuffle_shoe();
+        let mut tally = Tally::default();
+
+        for _ in 0..rounds {
+            let bet = self.ramp.bet(self.count(&shoe));
+            game.accept_user_bet(bet);
+            game.deal_initial_cards();
+            loop {
+                match game.get_state() {
+                    GameState::PlayerTurn { player_hands, dealer_hand, active_hand_index, .. } => {
+                        // Every card on the table counts, the dealer's hole card is still face down
+                        let visible: Vec<Card> = player_hands
+                            .iter()
+                            .flat_map(|hand| hand.cards.iter())
+                            .chain(dealer_hand.cards.first())
+                            .cloned()
+                            .collect();
+                        Self::observe(&mut shoe, &visible, &mut tally.report);
+                        let action = self.decide(player_hands, dealer_hand, *active_hand_index, self.count(&shoe));
+                        game.process_player_action(action, 0);
+                    }
+                    GameState::DealerTurn { .. } => game.next_dealer_turn(),
+                    _ => break,
+                }
+            }
+            let GameState::RoundComplete { player_hands, dealer_hand, .. } = game.get_state() else {
+                break;
+            };
+            tally.add_round(bet, player_hands);
+            let table: Vec<Card> = player_hands.iter().chain([dealer_hand]).flat_map(|hand| hand.cards.iter().cloned()).collect();
+            Self::observe(&mut shoe, &table, &mut tally.report);
+            shoe.end_round();
+            game.next_round();
+            if shoe.needs_shuffle() {
+                game.shuffle_shoe();
+                shoe.shuffle();
+                tally.report.shuffles += 1;
+            }
+        }
+        tally.finish()
+    }
+
+    /// The play for the active hand, by the same rules the table allows. `true_count`
+    /// is taken with the cards on the table, for the index plays.
+    fn decide(&self, hands: &[Hand], dealer_hand: &Hand, active: usize, true_count: i32) -> GameAction {
+        let (Some(hand), Some(up)) = (hands.get(active), dealer_hand.cards.first()) else {
+            return GameAction::Stand;
+        };
+        let first_two_cards = hand.cards.len() == 2;
+        let can_double = first_two_cards && (hands.len() == 1 || self.rules.double_after_split);
+        let can_split = first_two_cards && hand.cards[0].rank == hand.cards[1].rank;
+        let situation = Situation::new(&hand.cards, up, can_double, can_split, false);
+        let play = match self.strategy {
+            PlayerStrategy::MimicDealer if hand_total(&hand.cards).0 < 17 => Play::Hit,
+            PlayerStrategy::MimicDealer => Play::Stand,
+            PlayerStrategy::Basic => best_play(&situation, &self.rules),
+            PlayerStrategy::Deviations => self
+                .deviations
+                .iter()
+                .find(|deviation| {
+                    deviation.category == situation.category
+                        && deviation.total == situation.total
+                        && deviation.dealer_up == situation.dealer_up
+                })
+                .map(|deviation| deviation.correct_play(true_count, &self.rules))
+                .unwrap_or_else(|| best_play(&situation, &self.rules)),
+        };
+        match play {
+            Play::Stand => GameAction::Stand,
+            Play::Double if can_double => GameAction::Double,
+            Play::Split if can_split => GameAction::Split,
+            // The engine has no surrender, and a double it can't take is a hit
+            _ => GameAction::Hit,
+        }
+    }
+
+    /// "6 decks, 75% penetration, Basic strategy, bets 1-12 units of $10 on the Hi-Lo count"
+    fn describe(&self) -> String {
+        let bets = match self.ramp.spread {
+            1 => format!("flat ${} bets", self.ramp.unit),
+            spread => format!("bets 1-{} units of ${} on the {} count", spread, self.ramp.unit, self.system),
+        };
+        let strategy = match self.strategy {
+            PlayerStrategy::Basic => "basic strategy",
+            PlayerStrategy::Deviations => "basic strategy with index plays",
+            PlayerStrategy::MimicDealer => "mimicking the dealer",
+        };
+        format!("{} decks, {:.0}% penetration, {}, {}", self.rules.decks, self.rules.penetration * 100.0, strategy, bets)
+    }
+}
+
+/// The report the way a person reads it
+fn human_report(simulation: &Simulation, report: &SimulationReport) -> String {
+    let percent = |share: f64| format!("{:.2}%", share * 100.0);
+    let mut out = String::new();
+    let _ = writeln!(out, "Simulated {} rounds: {}", report.rounds, simulation.describe());
+    let _ = writeln!(out);
+    let sign = if report.ev_per_hand < 0.0 { "-" } else { "" };
+    let _ = writeln!(out, "EV per hand: {}${:.4} ({:+.3}% of the initial bet)", sign, report.ev_per_hand.abs(), -report.house_edge);
+    let _ = writeln!(out, "House edge: {:.3}%", report.house_edge);
+    let _ = writeln!(out, "Standard deviation: {:.3} initial bets per hand", report.std_dev);
+    let _ = match report.shoe_ran_out {
+        0 => writeln!(out, "Hands: {}, {} shuffles", report.hands, report.shuffles),
+        ran_out => writeln!(out, "Hands: {}, {} shuffles, {} of them when the shoe ran out mid-round", report.hands, report.shuffles, ran_out),
+    };
+    let _ = writeln!(
+        out,
+        "Wins: {}, blackjacks: {}, pushes: {}, losses: {}",
+        percent(report.win_rate),
+        percent(report.blackjack_rate),
+        percent(report.push_rate),
+        percent(report.loss_rate),
+    );
+    let _ = writeln!(out, "Wagered: ${:.2}, net: ${:.2}", report.wagered, report.net);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Round results, in initial bets");
+    for bucket in &report.outcomes {
+        let share = bucket.rounds as f64 / report.rounds.max(1) as f64;
+        let _ = writeln!(out, "  {:>+5.1}: {:>9} ({})", bucket.result, bucket.rounds, percent(share));
+    }
+    out
+}
+
+/// Runs the simulate subcommand, returns what to print
+pub fn simulate(args: &SimulateArgs, settings: &Settings) -> Result<String, Box<dyn Error>> {
+    let simulation = Simulation::new(args, settings)?;
+    let report = simulation.run(args.rounds);
+    match args.format {
+        OutputFormat::Human => Ok(human_report(&simulation, &report)),
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(&report)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use crate::settings::config::Settings;
+    use super::{simulate, SimulateArgs, Simulation};
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        args: SimulateArgs,
+    }
+
+    fn args(line: &str) -> SimulateArgs {
+        Cli::parse_from(std::iter::once("simulate").chain(line.split_whitespace())).args
+    }
+
+    #[test]
+    fn every_round_and_hand_is_accounted_for() {
+        let simulation = Simulation::new(&args("--decks 1 --spread 1"), &Settings::default()).unwrap();
+        let report = simulation.run(2_000);
+        assert_eq!(report.rounds, 2_000);
+        assert!(report.hands >= report.rounds);
+        assert!(report.shuffles > 0, "a single deck runs out of cards");
+        assert_eq!(report.outcomes.iter().map(|bucket| bucket.rounds).sum::<u64>(), 2_000);
+        let rates = report.win_rate + report.blackjack_rate + report.push_rate + report.loss_rate;
+        assert!((rates - 1.0).abs() < 1e-9, "{}", rates);
+        // Flat bets of one unit, so the edge is the loss per round
+        assert_eq!(report.initial_bets, 20_000.0);
+        assert!((report.house_edge + report.ev_per_hand * 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn reports_come_as_text_or_json() {
+        let text = simulate(&args("--rounds 200 --strategy mimic-dealer"), &Settings::default()).unwrap();
+        assert!(text.starts_with("Simulated 200 rounds: 6 decks, 75% penetration, mimicking the dealer, bets 1-12 units of $10"), "{}", text);
+        assert!(text.contains("House edge: "));
+
+        let json = simulate(&args("--rounds 200 --format json --spread 1"), &Settings::default()).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(report["rounds"], 200);
+        assert!(report["outcomes"].as_array().is_some_and(|outcomes| !outcomes.is_empty()));
+    }
+
+    #[test]
+    fn index_plays_need_a_true_count() {
+        let error = Simulation::new(&args("--strategy deviations --count ko"), &Settings::default()).err().unwrap();
+        assert!(error.contains("KO has no true count"), "{}", error);
+
+        let simulation = Simulation::new(&args("--strategy deviations --spread 1"), &Settings::default()).unwrap();
+        assert_eq!(simulation.run(500).rounds, 500);
+    }
+
+    #[test]
+    fn a_shoe_that_runs_out_counts_as_a_shuffle() {
+        let simulation = Simulation::new(&args("--decks 1 --penetration 90 --spread 1"), &Settings::default()).unwrap();
+        let report = simulation.run(2_000);
+        assert!(report.shoe_ran_out > 0);
+        assert!(report.shuffles > report.shoe_ran_out);
+    }
+}
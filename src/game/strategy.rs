@@ -0,0 +1,300 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use blackjack_engine::card::{Card, Rank};
+use crate::game::rules::HouseRules;
+
+/// What basic strategy says to do with a hand
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Play {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+}
+
+impl fmt::Display for Play {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Play::Hit => "Hit",
+            Play::Stand => "Stand",
+            Play::Double => "Double",
+            Play::Split => "Split",
+            Play::Surrender => "Surrender",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// The three tables basic strategy is learned from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandCategory {
+    Hard,
+    Soft,
+    Pair,
+}
+
+impl fmt::Display for HandCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            HandCategory::Hard => "Hard totals",
+            HandCategory::Soft => "Soft totals",
+            HandCategory::Pair => "Pairs",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// When the strategy hint is shown during the player's turn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HintMode {
+    Off,
+    /// Only after pressing the hint key, until the next action
+    OnRequest,
+    Always,
+}
+
+impl HintMode {
+    /// Same order as the options of the strategy hints setting
+    pub const ALL: [HintMode; 3] = [HintMode::Off, HintMode::OnRequest, HintMode::Always];
+
+    pub fn next(&self) -> HintMode {
+        match self {
+            HintMode::Off => HintMode::OnRequest,
+            HintMode::OnRequest => HintMode::Always,
+            HintMode::Always => HintMode::Off,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HintMode::Off => "Off",
+            HintMode::OnRequest => "On Request",
+            HintMode::Always => "Always",
+        }
+    }
+}
+
+/// Blackjack value of a single card, aces count 11
+pub fn card_value(card: &Card) -> u8 {
+    match card.rank {
+        Rank::Two => 2,
+        Rank::Three => 3,
+        Rank::Four => 4,
+        Rank::Five => 5,
+        Rank::Six => 6,
+        Rank::Seven => 7,
+        Rank::Eight => 8,
+        Rank::Nine => 9,
+        Rank::Ten | Rank::Jack | Rank::Queen | Rank::King => 10,
+        Rank::Ace => 11,
+    }
+}
+
+/// Best total of `cards`, and whether an ace is still counted as 11
+pub fn hand_total(cards: &[Card]) -> (u8, bool) {
+    let mut total: u8 = cards.iter().map(card_value).sum();
+    let mut soft_aces = cards.iter().filter(|card| card.rank == Rank::Ace).count();
+    while total > 21 && soft_aces > 0 {
+        total -= 10;
+        soft_aces -= 1;
+    }
+    (total, soft_aces > 0)
+}
+
+/// A player hand as basic strategy sees it, against the dealer's up card
+#[derive(Clone, Debug, PartialEq)]
+pub struct Situation {
+    pub category: HandCategory,
+    /// Hand total, or the value of one card for pairs
+    pub total: u8,
+    /// 2 to 11, an ace is 11
+    pub dealer_up: u8,
+    pub can_double: bool,
+    pub can_split: bool,
+    /// Only the first two cards of an unsplit hand can be surrendered
+    pub can_surrender: bool,
+}
+
+impl Situation {
+    /// `can_double` and `can_split` say whether the table allows them right now.
+    /// A pair that can't be split is played by its total.
+    pub fn new(cards: &[Card], dealer_up: &Card, can_double: bool, can_split: bool, can_surrender: bool) -> Situation {
+        let (total, soft) = hand_total(cards);
+        let is_pair = cards.len() == 2 && card_value(&cards[0]) == card_value(&cards[1]);
+        let category = match (is_pair && can_split, soft) {
+            (true, _) => HandCategory::Pair,
+            (false, true) => HandCategory::Soft,
+            (false, false) => HandCategory::Hard,
+        };
+        Situation {
+            category,
+            total: if category == HandCategory::Pair { card_value(&cards[0]) } else { total },
+            dealer_up: card_value(dealer_up),
+            can_double,
+            can_split,
+            can_surrender,
+        }
+    }
+
+    /// How the hand is named in hints, e.g. "Soft 18" or "Pair of 8s"
+    pub fn describe(&self) -> String {
+        let dealer = match self.dealer_up {
+            11 => "an Ace".to_string(),
+            8 => "an 8".to_string(),
+            up => format!("a {}", up),
+        };
+        let hand = match (self.category, self.total) {
+            (HandCategory::Pair, 11) => "Pair of Aces".to_string(),
+            (HandCategory::Pair, value) => format!("Pair of {}s", value),
+            (HandCategory::Soft, total) => format!("Soft {}", total),
+            (HandCategory::Hard, total) => format!("Hard {}", total),
+        };
+        format!("{} against {}", hand, dealer)
+    }
+}
+
+/// The basic strategy play for `situation` under `rules`. Doubles and
+/// surrenders that aren't allowed fall back to hitting or standing.
+pub fn best_play(situation: &Situation, rules: &HouseRules) -> Play {
+    let up = situation.dealer_up;
+    let h17 = rules.dealer_hits_soft_17;
+
+    if situation.category == HandCategory::Pair && should_split(situation.total, up, rules) {
+        return Play::Split;
+    }
+    // A pair that stays together plays like its hard total, aces like soft 12
+    let (total, soft) = match situation.category {
+        HandCategory::Pair if situation.total == 11 => (12, true),
+        HandCategory::Pair => (situation.total * 2, false),
+        HandCategory::Soft => (situation.total, true),
+        HandCategory::Hard => (situation.total, false),
+    };
+
+    if rules.surrender && situation.can_surrender && !soft {
+        let surrender = match (total, up) {
+            (16, 9..=11) => true,
+            (15, 10) => true,
+            (15 | 17, 11) => h17,
+            _ => false,
+        };
+        if surrender {
+            return Play::Surrender;
+        }
+    }
+
+    // Double when allowed, otherwise `fallback`
+    let double = |fallback: Play| if situation.can_double { Play::Double } else { fallback };
+
+    if soft {
+        return match (total, up) {
+            (13 | 14, 5 | 6) => double(Play::Hit),
+            (15 | 16, 4..=6) => double(Play::Hit),
+            (17, 3..=6) => double(Play::Hit),
+            (18, 2) if h17 => double(Play::Stand),
+            (18, 3..=6) => double(Play::Stand),
+            (18, 2 | 7 | 8) => Play::Stand,
+            (18, _) => Play::Hit,
+            (19, 6) if h17 => double(Play::Stand),
+            (19..=21, _) => Play::Stand,
+            _ => Play::Hit,
+        };
+    }
+
+    match (total, up) {
+        (9, 3..=6) => double(Play::Hit),
+        (10, 2..=9) => double(Play::Hit),
+        (11, 2..=10) => double(Play::Hit),
+        (11, 11) if h17 || rules.decks == 1 => double(Play::Hit),
+        (12, 4..=6) => Play::Stand,
+        (13..=16, 2..=6) => Play::Stand,
+        (17.., _) => Play::Stand,
+        _ => Play::Hit,
+    }
+}
+
+/// Whether a pair of cards worth `value` each is split against `up`
+fn should_split(value: u8, up: u8, rules: &HouseRules) -> bool {
+    let das = rules.double_after_split;
+    match value {
+        11 | 8 => true,
+        10 | 5 => false,
+        9 => matches!(up, 2..=6 | 8 | 9),
+        7 => up <= 7,
+        6 => (3..=6).contains(&up) || das && up == 2,
+        4 => das && (5..=6).contains(&up),
+        _ => (4..=7).contains(&up) || das && (2..=3).contains(&up),
+    }
+}
+
+/// One line on why `play` is right, for hints and trainer feedback
+pub fn explain(situation: &Situation, play: Play) -> String {
+    let total = match (situation.category, situation.total) {
+        (HandCategory::Pair, 11) => 12,
+        (HandCategory::Pair, value) => value * 2,
+        (_, total) => total,
+    };
+    let why = match play {
+        Play::Split => "splitting turns one weak hand into two better ones",
+        Play::Double => "the dealer is likely to finish weaker than one more card leaves you",
+        Play::Surrender => "this hand loses often enough that half the bet back is worth more",
+        Play::Stand if situation.dealer_up <= 6 => "the dealer shows a bust card, let them take the risk",
+        Play::Stand => "your total is strong enough to stand on",
+        Play::Hit if situation.category == HandCategory::Soft || total <= 11 => "one more card can't bust you",
+        Play::Hit if situation.dealer_up >= 7 => "the dealer likely makes 17 or more, so you have to improve",
+        Play::Hit => "the dealer busts too rarely against this card to stand",
+    };
+    format!("{}: {}, {}", situation.describe(), play, why)
+}
+
+#[cfg(test)]
+mod tests {
+    use blackjack_engine::card::{Card, Rank, Suit};
+    use crate::game::rules::HouseRules;
+    use super::{best_play, HandCategory, Play, Situation};
+
+    fn card(rank: Rank) -> Card {
+        Card { rank, suit: Suit::Spades }
+    }
+
+    fn play(first: Rank, second: Rank, up: Rank, rules: &HouseRules) -> Play {
+        let situation = Situation::new(&[card(first), card(second)], &card(up), true, true, true);
+        best_play(&situation, rules)
+    }
+
+    #[test]
+    fn follows_the_basic_strategy_tables() {
+        let rules = HouseRules::default();
+        assert_eq!(play(Rank::Ten, Rank::Six, Rank::Six, &rules), Play::Stand);
+        assert_eq!(play(Rank::Ten, Rank::Six, Rank::Ten, &rules), Play::Hit);
+        assert_eq!(play(Rank::Six, Rank::Five, Rank::Ten, &rules), Play::Double);
+        assert_eq!(play(Rank::Ace, Rank::Seven, Rank::Nine, &rules), Play::Hit);
+        assert_eq!(play(Rank::Eight, Rank::Eight, Rank::Ace, &rules), Play::Split);
+        assert_eq!(play(Rank::King, Rank::Queen, Rank::Six, &rules), Play::Stand);
+    }
+
+    #[test]
+    fn house_rules_change_the_play() {
+        let mut rules = HouseRules::default();
+        assert_eq!(play(Rank::Six, Rank::Five, Rank::Ace, &rules), Play::Hit);
+        assert_eq!(play(Rank::Four, Rank::Four, Rank::Five, &rules), Play::Split);
+
+        rules.dealer_hits_soft_17 = true;
+        rules.double_after_split = false;
+        rules.surrender = true;
+        assert_eq!(play(Rank::Six, Rank::Five, Rank::Ace, &rules), Play::Double);
+        assert_eq!(play(Rank::Four, Rank::Four, Rank::Five, &rules), Play::Hit);
+        assert_eq!(play(Rank::Ten, Rank::Six, Rank::Ten, &rules), Play::Surrender);
+    }
+
+    #[test]
+    fn unavailable_plays_fall_back() {
+        let situation = Situation::new(&[card(Rank::Three), card(Rank::Three), card(Rank::Five)], &card(Rank::Six), false, false, false);
+        assert_eq!(situation.category, HandCategory::Hard);
+        assert_eq!(best_play(&situation, &HouseRules::default()), Play::Hit);
+
+        let soft = Situation::new(&[card(Rank::Ace), card(Rank::Two), card(Rank::Four)], &card(Rank::Four), false, false, false);
+        assert_eq!(best_play(&soft, &HouseRules::default()), Play::Hit);
+    }
+}
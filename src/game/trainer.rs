@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use ratatui::prelude::{Line, Stylize};
+use serde::{Deserialize, Serialize};
+use crate::game::bet_ramp::BetTally;
+use crate::game::rules::HouseRules;
+use crate::game::strategy::{best_play, explain, HandCategory, Play, Situation};
+use crate::overlay::help::HelpOverlay;
+
+/// Mistakes listed on the report card, the most recent ones
+const REPORTED_MISTAKES: usize = 10;
+
+/// Decisions made in one hand category, and how many of them were right
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    pub correct: u32,
+    pub total: u32,
+}
+
+impl Score {
+    /// Percentage of correct decisions, `None` before the first one
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.total > 0).then(|| self.correct as f64 * 100.0 / self.total as f64)
+    }
+
+    pub fn add(&mut self, correct: bool) {
+        self.total += 1;
+        self.correct += correct as u32;
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.accuracy() {
+            Some(accuracy) => write!(f, "{}/{} ({:.0}%)", self.correct, self.total, accuracy),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+/// How a decision measured up to basic strategy
+#[derive(Clone, Debug, PartialEq)]
+pub enum Grade {
+    Correct,
+    /// What should have been played, and why
+    Mistake { best: Play, explanation: String },
+}
+
+/// Grades every decision of a training session against basic strategy
+#[derive(Clone, Debug, Default)]
+pub struct Trainer {
+    scores: BTreeMap<HandCategory, Score>,
+    /// "Hard 16 against a 10: played Stand, should Hit", oldest first
+    mistakes: Vec<String>,
+    bets: BetTally,
+}
+
+impl Trainer {
+    pub fn new() -> Trainer {
+        Trainer::default()
+    }
+
+    /// How `played` measures up to basic strategy, without counting it yet
+    pub fn grade(&self, situation: &Situation, played: Play, rules: &HouseRules) -> Grade {
+        let best = best_play(situation, rules);
+        if played == best {
+            return Grade::Correct;
+        }
+        Grade::Mistake { best, explanation: explain(situation, best) }
+    }
+
+    /// Counts a decision towards the hand's category. Only the first try is
+    /// recorded, a mistake still counts when it is taken back.
+    pub fn record(&mut self, situation: &Situation, played: Play, rules: &HouseRules) {
+        let best = best_play(situation, rules);
+        self.scores.entry(situation.category).or_default().add(played == best);
+        if played != best {
+            self.mistakes.push(format!("{}: played {}, should {}", situation.describe(), played, best));
+        }
+    }
+
+    /// Counts a bet towards the session, graded against what the ramp bets
+    pub fn grade_bet(&mut self, placed: f64, recommended: f64) {
+        self.bets.add(placed, recommended);
+    }
+
+    pub fn bets(&self) -> BetTally {
+        self.bets
+    }
+
+    pub fn score(&self, category: HandCategory) -> Score {
+        self.scores.get(&category).copied().unwrap_or_default()
+    }
+
+    pub fn overall(&self) -> Score {
+        self.scores.values().fold(Score::default(), |sum, score| Score {
+            correct: sum.correct + score.correct,
+            total: sum.total + score.total,
+        })
+    }
+
+    /// Accuracy per category, an overall letter grade, the bets against the
+    /// ramp once there are some, and the latest mistakes
+    pub fn report_card(&self) -> HelpOverlay {
+        let overall = self.overall();
+        let mut lines = vec![Line::from("Accuracy").bold().underlined()];
+        for category in [HandCategory::Hard, HandCategory::Soft, HandCategory::Pair] {
+            lines.push(Line::from(format!("  {}: {}", category, self.score(category))));
+        }
+        lines.push(Line::from(format!("  Overall: {}", overall)));
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("Grade: {}", letter_grade(overall.accuracy()))).bold());
+
+        if self.bets.on_ramp.total > 0 {
+            let bets = |count: u32| if count == 1 { "bet" } else { "bets" };
+            lines.push(Line::from(""));
+            lines.push(Line::from("Betting").bold().underlined());
+            lines.push(Line::from(format!("  On the ramp: {}", self.bets.on_ramp)));
+            lines.push(Line::from(format!("  Over-bet: ${} across {} {}", self.bets.over, self.bets.over_bets, bets(self.bets.over_bets))));
+            lines.push(Line::from(format!("  Under-bet: ${} across {} {}", self.bets.under, self.bets.under_bets, bets(self.bets.under_bets))));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("Latest mistakes").bold().underlined());
+        if self.mistakes.is_empty() {
+            lines.push(Line::from("  None so far"));
+        }
+        let skip = self.mistakes.len().saturating_sub(REPORTED_MISTAKES);
+        for mistake in self.mistakes.iter().skip(skip).rev() {
+            lines.push(Line::from(format!("  {}", mistake)));
+        }
+        HelpOverlay::page("Report card", lines)
+    }
+}
+
+fn letter_grade(accuracy: Option<f64>) -> &'static str {
+    match accuracy {
+        None => "-",
+        Some(accuracy) if accuracy >= 95.0 => "A",
+        Some(accuracy) if accuracy >= 85.0 => "B",
+        Some(accuracy) if accuracy >= 75.0 => "C",
+        Some(accuracy) if accuracy >= 65.0 => "D",
+        Some(_) => "F",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blackjack_engine::card::{Card, Rank, Suit};
+    use crate::game::rules::HouseRules;
+    use crate::game::strategy::{HandCategory, Play, Situation};
+    use super::{Grade, Score, Trainer};
+
+    fn situation(first: Rank, second: Rank, up: Rank) -> Situation {
+        let card = |rank| Card { rank, suit: Suit::Hearts };
+        Situation::new(&[card(first), card(second)], &card(up), true, true, false)
+    }
+
+    #[test]
+    fn scores_are_kept_per_category() {
+        let mut trainer = Trainer::new();
+        let rules = HouseRules::default();
+        assert_eq!(trainer.grade(&situation(Rank::Ten, Rank::Six, Rank::Six), Play::Stand, &rules), Grade::Correct);
+        trainer.record(&situation(Rank::Ten, Rank::Six, Rank::Six), Play::Stand, &rules);
+        let grade = trainer.grade(&situation(Rank::Eight, Rank::Eight, Rank::Ten), Play::Stand, &rules);
+        assert!(matches!(grade, Grade::Mistake { best: Play::Split, .. }));
+        // Only graded so far, a retry after taking a mistake back isn't scored
+        assert_eq!(trainer.overall(), Score { correct: 1, total: 1 });
+        trainer.record(&situation(Rank::Eight, Rank::Eight, Rank::Ten), Play::Stand, &rules);
+
+        assert_eq!(trainer.score(HandCategory::Hard), Score { correct: 1, total: 1 });
+        assert_eq!(trainer.score(HandCategory::Pair), Score { correct: 0, total: 1 });
+        assert_eq!(trainer.overall().to_string(), "1/2 (50%)");
+    }
+}
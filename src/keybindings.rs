@@ -0,0 +1,456 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use serde::{Deserialize, Serialize};
+use crate::paths::app_dir;
+
+/// Everything the user can do with a key. Screens ask the bindings which
+/// action a key press maps to instead of matching on keys themselves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Quit,
+    MainMenu,
+    Back,
+    Help,
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    ResetSetting,
+    PlaceBet,
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+    Hint,
+    HintMode,
+    NextRound,
+    LeaveTable,
+    ReportCard,
+    ShoeView,
+    Confirm,
+    Decline,
+}
+
+/// Where an action is available. Two actions sharing a context may not share a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Context {
+    /// Menus, settings and other list screens
+    Menu,
+    /// Typing in a bet at the table
+    Betting,
+    /// Playing out a hand
+    PlayerTurn,
+    /// Between two rounds
+    RoundOver,
+    /// A dialog drawn over any screen. Global actions other than Back don't reach it.
+    Modal,
+}
+
+const ALL_CONTEXTS: [Context; 4] = [Context::Menu, Context::Betting, Context::PlayerTurn, Context::RoundOver];
+
+impl Context {
+    /// Every action available in this context, screen specific ones first
+    pub fn actions(&self) -> Vec<Action> {
+        let (global, local): (Vec<Action>, Vec<Action>) = Action::ALL
+            .into_iter()
+            .filter(|action| action.contexts().contains(self))
+            .partition(|action| ALL_CONTEXTS.iter().all(|context| action.contexts().contains(context)));
+        local.into_iter().chain(global).collect()
+    }
+}
+
+impl Action {
+    pub const ALL: [Action; 24] = [
+        Action::Quit,
+        Action::MainMenu,
+        Action::Back,
+        Action::Help,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Select,
+        Action::ResetSetting,
+        Action::PlaceBet,
+        Action::Hit,
+        Action::Stand,
+        Action::Double,
+        Action::Split,
+        Action::Surrender,
+        Action::Hint,
+        Action::HintMode,
+        Action::NextRound,
+        Action::LeaveTable,
+        Action::ReportCard,
+        Action::ShoeView,
+        Action::Confirm,
+        Action::Decline,
+    ];
+
+    pub fn contexts(&self) -> &'static [Context] {
+        match self {
+            Action::Quit | Action::MainMenu | Action::Help => &ALL_CONTEXTS,
+            Action::Back => &[Context::Menu, Context::Betting, Context::PlayerTurn, Context::RoundOver, Context::Modal],
+            Action::Up | Action::Down | Action::Select => &[Context::Menu, Context::Modal],
+            Action::Left | Action::Right | Action::ResetSetting => &[Context::Menu],
+            Action::PlaceBet => &[Context::Betting],
+            Action::Hit
+            | Action::Stand
+            | Action::Double
+            | Action::Split
+            | Action::Surrender
+            | Action::Hint
+            | Action::HintMode => &[Context::PlayerTurn],
+            Action::NextRound | Action::LeaveTable => &[Context::RoundOver],
+            Action::ReportCard | Action::ShoeView => &[Context::Betting, Context::PlayerTurn, Context::RoundOver],
+            Action::Confirm | Action::Decline => &[Context::Modal],
+        }
+    }
+
+    /// Short name used in the footer
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::MainMenu => "Menu",
+            Action::Back => "Back",
+            Action::Help => "Help",
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Left => "Less",
+            Action::Right => "More",
+            Action::Select => "Select",
+            Action::ResetSetting => "Reset",
+            Action::PlaceBet => "Place Bet",
+            Action::Hit => "Hit",
+            Action::Stand => "Stand",
+            Action::Double => "Double",
+            Action::Split => "Split",
+            Action::Surrender => "Surrender",
+            Action::Hint => "Hint",
+            Action::HintMode => "Hints",
+            Action::NextRound => "Next Round",
+            Action::LeaveTable => "Leave",
+            Action::ReportCard => "Report",
+            Action::ShoeView => "Shoe",
+            Action::Confirm => "Yes",
+            Action::Decline => "No",
+        }
+    }
+}
+
+/// Keys typed into the bet while betting, they can't be bound to anything there
+fn is_bet_typing_key(code: KeyCode) -> bool {
+    matches!(code, KeyCode::Backspace) || matches!(code, KeyCode::Char(ch) if ch.is_ascii_digit())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// hjkl to move, letters for table actions
+    #[default]
+    Vim,
+    /// Arrow keys and the numeric keypad
+    Arrows,
+}
+
+/// The on-disk format, a preset plus per-action overrides
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct KeyBindingsFile {
+    preset: Preset,
+    bindings: BTreeMap<Action, Vec<String>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub key: KeyCode,
+    pub first: Action,
+    pub second: Action,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is bound to both {:?} and {:?}", key_label(self.key), self.first, self.second)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct KeyBindings {
+    keys: BTreeMap<Action, Vec<KeyCode>>,
+}
+
+impl KeyBindings {
+    pub fn preset(preset: Preset) -> KeyBindings {
+        use KeyCode::*;
+        let keys: Vec<(Action, Vec<KeyCode>)> = match preset {
+            Preset::Vim => vec![
+                (Action::Quit, vec![Char('q')]),
+                (Action::MainMenu, vec![Char('m')]),
+                (Action::Back, vec![Esc]),
+                (Action::Help, vec![Char('?')]),
+                (Action::Up, vec![Char('k'), Up]),
+                (Action::Down, vec![Char('j'), Down]),
+                (Action::Left, vec![Char('h'), Left]),
+                (Action::Right, vec![Char('l'), Right]),
+                (Action::Select, vec![Enter]),
+                (Action::ResetSetting, vec![Char('r')]),
+                (Action::PlaceBet, vec![Enter]),
+                (Action::Hit, vec![Char('h')]),
+                (Action::Stand, vec![Char('s')]),
+                (Action::Double, vec![Char('d')]),
+                (Action::Split, vec![Char('p')]),
+                (Action::Surrender, vec![Char('u')]),
+                (Action::Hint, vec![Char('b')]),
+                (Action::HintMode, vec![Char('v')]),
+                (Action::NextRound, vec![Char('y'), Enter]),
+                (Action::LeaveTable, vec![Char('n')]),
+                (Action::ReportCard, vec![Char('r')]),
+                (Action::ShoeView, vec![Char('x')]),
+                (Action::Confirm, vec![Char('y')]),
+                (Action::Decline, vec![Char('n')]),
+            ],
+            Preset::Arrows => vec![
+                (Action::Quit, vec![Char('q')]),
+                (Action::MainMenu, vec![Home]),
+                (Action::Back, vec![Esc]),
+                (Action::Help, vec![Char('?')]),
+                (Action::Up, vec![Up, Char('8')]),
+                (Action::Down, vec![Down, Char('2')]),
+                (Action::Left, vec![Left, Char('4')]),
+                (Action::Right, vec![Right, Char('6')]),
+                (Action::Select, vec![Enter, Char('5')]),
+                (Action::ResetSetting, vec![Char('r')]),
+                (Action::PlaceBet, vec![Enter]),
+                (Action::Hit, vec![Char('+'), Up]),
+                (Action::Stand, vec![Char('-'), Down]),
+                (Action::Double, vec![Char('*'), Right]),
+                (Action::Split, vec![Char('/'), Left]),
+                (Action::Surrender, vec![Char('9')]),
+                (Action::Hint, vec![Char('.')]),
+                (Action::HintMode, vec![Char('0')]),
+                (Action::NextRound, vec![Enter, Char('+')]),
+                (Action::LeaveTable, vec![Delete, Char('-')]),
+                (Action::ReportCard, vec![Char('r')]),
+                (Action::ShoeView, vec![Char('x')]),
+                (Action::Confirm, vec![Char('y'), Char('+')]),
+                (Action::Decline, vec![Char('n'), Char('-')]),
+            ],
+        };
+        KeyBindings { keys: keys.into_iter().collect() }
+    }
+
+    /// The action `key` triggers in `context`, if any
+    pub fn action(&self, context: Context, key: &KeyEvent) -> Option<Action> {
+        self.keys
+            .iter()
+            .find(|(action, keys)| action.contexts().contains(&context) && keys.contains(&key.code))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Label of the first key bound to `action`, e.g. "H" or "Enter"
+    pub fn key_hint(&self, action: Action) -> String {
+        self.keys(action).first().map(|code| key_label(*code)).unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Every key that would trigger two different actions in the same context
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        let mut conflicts = vec![];
+        for (i, first) in Action::ALL.iter().enumerate() {
+            for second in &Action::ALL[i + 1..] {
+                let shared_context = first.contexts().iter().any(|c| second.contexts().contains(c));
+                if !shared_context {
+                    continue;
+                }
+                for key in self.keys(*first) {
+                    if self.keys(*second).contains(key) {
+                        conflicts.push(Conflict { key: *key, first: *first, second: *second });
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Bindings that would swallow keys needed to type a bet
+    fn reserved_key_clashes(&self) -> Vec<String> {
+        Action::ALL
+            .iter()
+            .filter(|action| action.contexts().contains(&Context::Betting))
+            .flat_map(|action| {
+                self.keys(*action)
+                    .iter()
+                    .filter(|key| is_bet_typing_key(**key))
+                    .map(move |key| format!("{} is needed to type bets and can't be bound to {:?}", key_label(*key), action))
+            })
+            .collect()
+    }
+
+    /// Loads the bindings file, falling back to the vim preset if it is missing
+    /// or unusable. Returns the bindings and any problems worth telling the user about.
+    pub fn load() -> (KeyBindings, Vec<String>) {
+        let Ok(contents) = fs::read_to_string(keybindings_path()) else {
+            return (KeyBindings::preset(Preset::default()), vec![]);
+        };
+        match KeyBindings::parse(&contents) {
+            Ok(bindings) => (bindings, vec![]),
+            Err(problems) => (KeyBindings::preset(Preset::default()), problems),
+        }
+    }
+
+    fn parse(contents: &str) -> Result<KeyBindings, Vec<String>> {
+        let file: KeyBindingsFile = serde_json::from_str(contents)
+            .map_err(|err| vec![format!("keybindings.json: {}", err)])?;
+
+        let mut bindings = KeyBindings::preset(file.preset);
+        let mut problems = vec![];
+        for (action, names) in file.bindings {
+            let mut keys = vec![];
+            for name in names {
+                match parse_key(&name) {
+                    Some(code) => keys.push(code),
+                    None => problems.push(format!("keybindings.json: unknown key {:?} for {:?}", name, action)),
+                }
+            }
+            bindings.keys.insert(action, keys);
+        }
+        problems.extend(bindings.conflicts().iter().map(|c| format!("keybindings.json: {}", c)));
+        problems.extend(bindings.reserved_key_clashes());
+
+        if problems.is_empty() {
+            Ok(bindings)
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+pub fn keybindings_path() -> PathBuf {
+    app_dir().join("keybindings.json")
+}
+
+static BINDINGS: OnceLock<KeyBindings> = OnceLock::new();
+
+/// Sets the bindings used for the rest of the session. Only the first call has any effect.
+pub fn init(bindings: KeyBindings) {
+    let _ = BINDINGS.set(bindings);
+}
+
+/// The active bindings, the vim preset until `init` is called
+pub fn bindings() -> &'static KeyBindings {
+    BINDINGS.get_or_init(|| KeyBindings::preset(Preset::default()))
+}
+
+/// Parses a key name as written in the bindings file: a single character,
+/// or one of Up, Down, Left, Right, Enter, Esc, Backspace, Tab, Space, Home,
+/// End, PageUp, PageDown, Delete, Insert and F1-F12.
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(ch));
+    }
+    let code = match name.to_ascii_lowercase().as_str() {
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "enter" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "backspace" => KeyCode::Backspace,
+        "tab" => KeyCode::Tab,
+        "space" => KeyCode::Char(' '),
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        other => {
+            let number = other.strip_prefix('f')?.parse::<u8>().ok()?;
+            if !(1..=12).contains(&number) {
+                return None;
+            }
+            KeyCode::F(number)
+        }
+    };
+    Some(code)
+}
+
+/// How a key is shown to the user
+pub fn key_label(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(ch) => ch.to_uppercase().to_string(),
+        KeyCode::Up => "↑".to_string(),
+        KeyCode::Down => "↓".to_string(),
+        KeyCode::Left => "←".to_string(),
+        KeyCode::Right => "→".to_string(),
+        KeyCode::Backspace => "Bksp".to_string(),
+        KeyCode::PageUp => "PgUp".to_string(),
+        KeyCode::PageDown => "PgDn".to_string(),
+        KeyCode::Delete => "Del".to_string(),
+        KeyCode::Insert => "Ins".to_string(),
+        KeyCode::F(number) => format!("F{}", number),
+        other => format!("{:?}", other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use super::{Action, Context, KeyBindings, Preset};
+
+    #[test]
+    fn presets_have_no_conflicts() {
+        for preset in [Preset::Vim, Preset::Arrows] {
+            let bindings = KeyBindings::preset(preset);
+            assert_eq!(bindings.conflicts(), vec![], "{:?}", preset);
+            assert!(bindings.reserved_key_clashes().is_empty(), "{:?}", preset);
+        }
+    }
+
+    #[test]
+    fn same_key_resolves_per_context() {
+        let bindings = KeyBindings::preset(Preset::Vim);
+        let h = KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE);
+        assert_eq!(bindings.action(Context::Menu, &h), Some(Action::Left));
+        assert_eq!(bindings.action(Context::PlayerTurn, &h), Some(Action::Hit));
+        assert_eq!(bindings.action(Context::Betting, &h), None);
+    }
+
+    #[test]
+    fn dialogs_only_answer_to_their_own_keys() {
+        let bindings = KeyBindings::preset(Preset::Vim);
+        let key = |ch| KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE);
+        assert_eq!(bindings.action(Context::Modal, &key('y')), Some(Action::Confirm));
+        assert_eq!(bindings.action(Context::Modal, &key('j')), Some(Action::Down));
+        assert_eq!(bindings.action(Context::Modal, &key('q')), None);
+        assert_eq!(Context::Modal.actions().last(), Some(&Action::Back));
+    }
+
+    #[test]
+    fn overrides_are_checked_for_conflicts() {
+        let problems = KeyBindings::parse(r#"{ "bindings": { "stand": ["h"], "split": ["F13"] } }"#).unwrap_err();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+
+        let bindings = KeyBindings::parse(r#"{ "preset": "arrows", "bindings": { "hit": ["Space"] } }"#).unwrap();
+        assert_eq!(bindings.keys(Action::Hit), &[KeyCode::Char(' ')]);
+        assert_eq!(bindings.keys(Action::Stand), &[KeyCode::Char('-'), KeyCode::Down]);
+    }
+
+    #[test]
+    fn digits_stay_free_for_betting() {
+        let problems = KeyBindings::parse(r#"{ "bindings": { "main_menu": ["0"] } }"#).unwrap_err();
+        assert!(problems[0].contains("type bets"), "{:?}", problems);
+    }
+}
@@ -1,13 +1,6 @@
-use ratatui::{
-    backend::{Backend, CrosstermBackend},
-    crossterm::{
-        event::{DisableMouseCapture, EnableMouseCapture},
-        execute,
-        terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-    },
-    Terminal,
-};
-use std::{error::Error, io};
+use ratatui::{backend::Backend, Terminal};
+use std::{cell::RefCell, error::Error, io, io::Write, rc::Rc};
+use clap::{Parser, Subcommand};
 
 mod model;
 mod app;
@@ -16,76 +9,194 @@ mod constants;
 mod menu;
 mod settings;
 mod game;
+mod drill;
+mod event;
+mod error;
+mod paths;
+mod terminal;
+mod navigation;
+mod overlay;
+mod keybindings;
+mod theme;
+mod rng;
+#[cfg(test)]
+mod testing;
 
 use crate::menu::menu_screen::MenuScreen;
-use crate::model::{Model, ModelResponse};
-use crate::settings::settings_screen::SettingsScreen;
+use crate::model::ModelResponse;
 use crate::app::App;
-use crate::game::game_screen::GameScreen;
+use crate::game::animation::AnimationSpeed;
+use crate::game::simulation::{simulate, SimulateArgs};
+use crate::event::{AppEvent, EventHandler, EventSource, TICK_RATE};
+use crate::error::error_screen::ErrorScreen;
+use crate::navigation::{Navigation, Navigator};
+use crate::overlay::overlay_layer::OverlayLayer;
+use crate::keybindings::{bindings, Action, KeyBindings};
+use crate::theme::Theme;
+use crate::settings::config::Settings;
+use crate::ui::{fits_minimum_size, render_too_small};
+
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// How fast cards and chips move around the table, overrides the setting for this session
+    #[arg(long, value_enum)]
+    animation_speed: Option<AnimationSpeed>,
+    /// Deals the drills the same cards every time it is given the same seed
+    #[arg(long)]
+    seed: Option<u64>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Plays rounds against the engine without a terminal and reports the player's results
+    Simulate(SimulateArgs),
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
-    // setup terminal
-    enable_raw_mode()?;
-    let mut stderr = io::stderr(); // This is a special case. Normally using stdout is fine
-    execute!(stderr, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stderr);
-    let mut terminal = Terminal::new(backend)?;
+    let cli = Cli::parse();
+    let (bindings, binding_problems) = KeyBindings::load();
+    keybindings::init(bindings);
+    let (theme, theme_problems) = Theme::load();
+    theme::init(theme);
+    let (mut settings, settings_problems) = Settings::load();
+    if let Some(speed) = cli.animation_speed {
+        settings = settings.with_session_animation_speed(speed);
+    }
+    if let Some(seed) = cli.seed {
+        settings = settings.with_session_seed(seed);
+    }
+
+    // a simulation prints its report and never opens the table
+    if let Some(Command::Simulate(args)) = &cli.command {
+        for problem in settings_problems {
+            eprintln!("{problem}");
+        }
+        let report = match simulate(args, &settings) {
+            Ok(report) => report,
+            Err(err) => {
+                eprintln!("error: {err}");
+                std::process::exit(2);
+            }
+        };
+        // piping into `head` closes stdout early, that is no reason to panic
+        return match writeln!(io::stdout(), "{report}") {
+            Err(err) if err.kind() != io::ErrorKind::BrokenPipe => Err(err.into()),
+            _ => Ok(()),
+        };
+    }
+
+    // setup terminal, the panic hook restores it if anything goes wrong later on
+    terminal::install_panic_hook();
+    let mut terminal = terminal::init()?;
 
     // create app and run it
-    let mut app = App::new();
-    let res = run_app(&mut terminal, &mut app);
+    let mut app = App::new(Rc::new(RefCell::new(settings)));
+    app.startup_messages.extend(binding_problems);
+    app.startup_messages.extend(theme_problems);
+    app.startup_messages.extend(settings_problems);
+    let mut events = EventHandler::new(TICK_RATE);
+    let res = run_app(&mut terminal, &mut app, &mut events);
 
     // restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    terminal::restore()?;
 
     if let Err(err) = res {
-        println!("{err:?}")
+        let report = format!("{err:?}");
+        match terminal::write_crash_report(&report) {
+            Ok(path) => eprintln!("{report}\nA report was written to {}", path.display()),
+            Err(_) => eprintln!("{report}"),
+        }
+        return Err(err.into());
     }
     Ok(())
 }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> io::Result<()> {
-    let mut screen: Box<dyn Model> = Box::new(MenuScreen::new());
+fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App, events: &mut impl EventSource) -> io::Result<()> {
+    let mut navigator = Navigator::new(Box::new(MenuScreen::new(app.settings.clone())));
+    let mut overlays = OverlayLayer::new();
+    for message in app.startup_messages.drain(..) {
+        overlays.toast(&message);
+    }
+    // Returns the area drawn into, so input can be held back while it is too small to use
+    let mut draw = |navigator: &mut Navigator, overlays: &OverlayLayer| {
+        terminal.draw(|f| {
+            if !fits_minimum_size(f.area()) {
+                render_too_small(f);
+                return;
+            }
+            navigator.current().ui(f);
+            overlays.render(f);
+        })
+        .map(|frame| frame.area)
+    };
+    let mut area = draw(&mut navigator, &overlays)?;
+
     loop {
-        terminal.draw(|f| screen.ui(f))?;
-
-        loop {
-            // This may look like a nested loop, but it prevents unnecessary
-            // re-renders of the terminal ui
-            let response = screen.update();
-            match response {
-                Ok(ModelResponse::Refresh) => break,
-                Ok(ModelResponse::Exit) => return Ok(()),
-                Ok(ModelResponse::NavToMainMenu) => {
-                    screen = Box::new(MenuScreen::new());
-                    break;
-                }
-                Ok(ModelResponse::NavToGame) => {
-                    screen = Box::new(GameScreen::new());
-                    break;
-                }
-                Ok(ModelResponse::NavToSettings) => {
-                    screen = Box::new(SettingsScreen::new());
-                    break;
-                }
-                Ok(ModelResponse::NavToTutorial) => {
-                    screen = Box::new(SettingsScreen::new());
-                    break;
-                }
-                Ok(ModelResponse::NavToHighScores) => {
-                    screen = Box::new(SettingsScreen::new());
-                    break;
-                }
+        let Some(event) = events.next()? else {
+            return Ok(());
+        };
+        // A resize always needs a redraw, even if the screen did not change
+        let mut redraw = matches!(event, AppEvent::Resize(..));
+        if event == AppEvent::Tick {
+            redraw |= overlays.tick();
+        }
+
+        // Nothing but quitting works while the screen can't be seen
+        if !fits_minimum_size(area) {
+            match &event {
+                AppEvent::Key(key) if bindings().keys(Action::Quit).contains(&key.code) => return Ok(()),
+                AppEvent::Key(_) | AppEvent::Mouse(_) => continue,
                 _ => {}
             }
-            break;
+        }
+
+        // An open modal keeps the input to itself
+        let Some(event) = overlays.intercept(event) else {
+            area = draw(&mut navigator, &overlays)?;
+            continue;
+        };
 
+        // The help is there on every screen, the screen itself never sees the key
+        if let AppEvent::Key(key) = &event {
+            if bindings().keys(Action::Help).contains(&key.code) {
+                overlays.open_help(navigator.current().help());
+                area = draw(&mut navigator, &overlays)?;
+                continue;
+            }
+        }
+
+        // A failing screen is swapped for the error screen, the session carries on
+        let response = navigator.current().update(&event).unwrap_or_else(|err| {
+            ModelResponse::Navigate(Navigation::Replace(Box::new(ErrorScreen::new(err))))
+        });
+        match response {
+            ModelResponse::NoOp => {}
+            ModelResponse::Refresh => redraw = true,
+            ModelResponse::Exit => return Ok(()),
+            ModelResponse::Navigate(navigation) => {
+                if !navigator.navigate(navigation) {
+                    overlays.toast("Nothing to continue yet");
+                }
+                redraw = true;
+            }
+            ModelResponse::OpenModal(modal) => {
+                overlays.open(modal);
+                redraw = true;
+            }
+            ModelResponse::Toast(message) => {
+                overlays.toast(&message);
+                redraw = true;
+            }
+            ModelResponse::OpenPage(page) => {
+                overlays.open_help(page);
+                redraw = true;
+            }
+        }
+        if redraw {
+            area = draw(&mut navigator, &overlays)?;
         }
     }
-}
\ No newline at end of file
+}
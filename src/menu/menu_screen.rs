@@ -1,132 +1,161 @@
-use ratatui::crossterm::event;
-use ratatui::crossterm::event::{Event, KeyCode};
+use std::fmt;
 use ratatui::Frame;
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
-use ratatui::prelude::{Alignment, Line};
-use ratatui::style::{Color, Stylize};
-use ratatui::widgets::{Block, Paragraph};
-use crate::menu::menu_screen::MainMenuOption::{Continue, HighScores, Play, Settings, Tutorial};
+use ratatui::layout::{Constraint, Direction, Layout};
+use crate::drill::deviation_screen::DeviationScreen;
+use crate::drill::drill_screen::DrillScreen;
+use crate::drill::flash_screen::FlashScreen;
+use crate::game::game_screen::GameScreen;
+use crate::menu::menu_screen::MainMenuOption::{Continue, Count, Deviations, Drill, Flash, Play, Settings, Train};
+use crate::menu::menu_widget::{Menu, MenuEvent, MenuItem};
+use crate::event::AppEvent;
+use crate::keybindings::{bindings, Action, Context};
 use crate::model::{Model, ModelResponse};
-use crate::ui::{render_border, render_sub_title_block, render_title_block, MenuNavigation};
+use crate::navigation::Navigation;
+use crate::overlay::modal::{Modal, ModalResult};
+use crate::settings::config::SharedSettings;
+use crate::settings::settings_screen::SettingsScreen;
+use crate::ui::{render_border, render_sub_title_block, render_title_block, title_height};
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum MainMenuOption {
     Play,
     Continue,
-    Tutorial,
-    HighScores,
+    Train,
+    Drill,
+    Count,
+    Flash,
+    Deviations,
     Settings,
 }
 
-impl MainMenuOption {
-    pub fn to_string(&self) -> String {
-        match self {
-            Play => "Play".to_string(),
-            Continue => "Continue".to_string(),
-            Tutorial => "Tutorial".to_string(),
-            HighScores => "High Scores".to_string(),
-            Settings => "Settings".to_string(),
-        }
+impl fmt::Display for MainMenuOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Play => "Play",
+            Continue => "Continue",
+            Train => "Train",
+            Drill => "Drill",
+            Count => "Count",
+            Flash => "Flash",
+            Deviations => "Deviations",
+            Settings => "Settings",
+        };
+        write!(f, "{}", label)
     }
 }
 
-const MAIN_MENU_ITEMS: [MainMenuOption; 5] = [
-    Play,
-    Continue,
-    Tutorial,
-    HighScores,
-    Settings
-];
-
+const PLAYER_NAME_MODAL: &str = "player_name";
 
 pub struct MenuScreen {
-    active_menu_index: i8,
+    menu: Menu<MainMenuOption>,
+    settings: SharedSettings,
 }
 
 impl MenuScreen {
-    pub fn new() -> MenuScreen {
-        MenuScreen {
-            active_menu_index: 0,
-        }
-    }
+    pub fn new(settings: SharedSettings) -> MenuScreen {
+        let mut continue_item = MenuItem::new(Continue, Continue.to_string())
+            .hotkey('c')
+            .description("Back to the table you left");
+        // Nothing to go back to until a game was started
+        continue_item.set_enabled(false);
 
-    fn render_menu_body(&self, frame: &mut Frame, rect: Rect) {
-        let mut menu_body: Vec<Line<'_>> = vec![];
-
-        for (i, item) in MAIN_MENU_ITEMS.iter().enumerate() {
-            menu_body.push(Line::from(""));
-            let mut text = if self.active_menu_index == i as i8 {
-                "> ".to_string()
-            } else {
-                String::new()
-            };
-
-            text.push_str(item.to_string().as_str());
-
-            if self.active_menu_index == i as i8 {
-                menu_body.push(Line::from(text).fg(Color::Green))
-            } else {
-                menu_body.push(Line::from(text));
-            }
-        }
-
-        let menu_options = Paragraph::new(menu_body)
-            .bold()
-            .alignment(Alignment::Center)
-            .block(Block::default());
-        frame.render_widget(menu_options, rect);
-    }
-
-    fn return_navigation_target(&self) -> ModelResponse {
-        let selected_option = MAIN_MENU_ITEMS.get(self.active_menu_index as usize).unwrap();
-        match selected_option {
-            Continue => ModelResponse::NavToGame,
-            Play => ModelResponse::NavToGame,
-            Settings => ModelResponse::NavToSettings,
-            HighScores => ModelResponse::NavToHighScores,
-            Tutorial => ModelResponse::NavToHighScores,
+        MenuScreen {
+            menu: Menu::new(vec![
+                MenuItem::new(Play, Play.to_string())
+                    .hotkey('p')
+                    .description("Sit down at a new table"),
+                continue_item,
+                MenuItem::new(Train, Train.to_string())
+                    .hotkey('t')
+                    .description("Every decision graded against basic strategy"),
+                MenuItem::new(Drill, Drill.to_string())
+                    .hotkey('d')
+                    .description("Strategy flashcards, answer as fast as you can"),
+                MenuItem::new(Count, Count.to_string())
+                    .hotkey('o')
+                    .description("Keep the count in your head, the dealer asks for it now and then"),
+                MenuItem::new(Flash, Flash.to_string())
+                    .hotkey('f')
+                    .description("Cards flashed from a shoe, then give the running count"),
+                MenuItem::new(Deviations, Deviations.to_string())
+                    .hotkey('v')
+                    .description("Illustrious 18 and Fab 4, when the true count changes the play"),
+                MenuItem::new(Settings, Settings.to_string())
+                    .hotkey('s')
+                    .description("Decks, players and other table options"),
+            ]),
+            settings,
         }
     }
-}
-
-impl MenuNavigation for MenuScreen {
-    fn get_menu_length(&self) -> usize {
-        MAIN_MENU_ITEMS.len()
-    }
 
-    fn get_menu_index(&self) -> i8 {
-        self.active_menu_index
+    /// Continue only works while there is a table that was left
+    fn enable_continue(&mut self, enabled: bool) {
+        self.menu.items_mut().filter(|item| item.value == Continue).for_each(|item| item.set_enabled(enabled));
     }
 
-    fn set_menu_index(&mut self, index: i8) {
-        self.active_menu_index = index
+    fn return_navigation_target(&mut self, selected_option: MainMenuOption) -> ModelResponse {
+        let navigation = match selected_option {
+            Play => {
+                return ModelResponse::OpenModal(Modal::text_input(
+                    PLAYER_NAME_MODAL,
+                    "New game",
+                    "Who is sitting down at the table?",
+                    self.settings.borrow().player_name(),
+                ));
+            }
+            // Reopens the table that was left last
+            Continue => Navigation::Resume,
+            Train => {
+                let settings = self.settings.borrow();
+                let game = GameScreen::new(&settings, settings.player_name().to_string()).with_trainer();
+                drop(settings);
+                Navigation::Push(Box::new(game))
+            }
+            Drill => Navigation::Push(Box::new(DrillScreen::new(&self.settings.borrow()))),
+            Count => {
+                let settings = self.settings.borrow();
+                let game = GameScreen::new(&settings, settings.player_name().to_string()).with_count_quiz(&settings);
+                drop(settings);
+                Navigation::Push(Box::new(game))
+            }
+            Flash => Navigation::Push(Box::new(FlashScreen::new(self.settings.clone()))),
+            Deviations => Navigation::Push(Box::new(DeviationScreen::new(&self.settings.borrow()))),
+            Settings => Navigation::Push(Box::new(SettingsScreen::new(self.settings.clone()))),
+        };
+        ModelResponse::Navigate(navigation)
     }
 }
 
 impl Model for MenuScreen {
-    fn update(&mut self) -> std::io::Result<ModelResponse> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Release {
-                return Ok(ModelResponse::Refresh);
+    fn update(&mut self, event: &AppEvent) -> std::io::Result<ModelResponse> {
+        if let AppEvent::ModalClosed { id: PLAYER_NAME_MODAL, result: ModalResult::Text(name) } = event {
+            let settings = self.settings.borrow();
+            let name = match name.trim() {
+                "" => settings.player_name().to_string(),
+                name => name.to_string(),
+            };
+            let game = GameScreen::new(&settings, name);
+            drop(settings);
+            return Ok(ModelResponse::Navigate(Navigation::Push(Box::new(game))));
+        }
+        match self.menu.handle(event) {
+            MenuEvent::Activated(option) | MenuEvent::Clicked(option, _) => {
+                return Ok(self.return_navigation_target(option));
             }
-            return match key.code {
-                KeyCode::Char('q') => Ok(ModelResponse::Exit),
-                // More cursor down
-                KeyCode::Char('j') | KeyCode::Down => {
-                    self.increment_menu_index(1);
-                    return Ok(ModelResponse::Refresh);
-                }
-                // More cursor up
-                KeyCode::Char('k') | KeyCode::Up => {
-                    self.increment_menu_index(-1);
-                    return Ok(ModelResponse::Refresh);
-                }
-                KeyCode::Enter => {
-                    return Ok(self.return_navigation_target());
-                }
-                _ => Ok(ModelResponse::Refresh),
+            MenuEvent::Disabled(Continue) => {
+                return Ok(ModelResponse::Toast("Nothing to continue yet".to_string()));
             }
+            MenuEvent::Disabled(_) => return Ok(ModelResponse::NoOp),
+            MenuEvent::Moved => return Ok(ModelResponse::Refresh),
+            MenuEvent::Ignored => {}
+        }
+        let AppEvent::Key(key) = event else {
+            return Ok(ModelResponse::NoOp);
+        };
+        match bindings().action(Context::Menu, key) {
+            Some(Action::Quit) => Ok(ModelResponse::Exit),
+            _ => Ok(ModelResponse::NoOp),
         }
-        Ok(ModelResponse::Refresh)
     }
 
     fn ui(&mut self, frame: &mut Frame) {
@@ -138,9 +167,9 @@ impl Model for MenuScreen {
         let menu_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(14),
+                Constraint::Length(title_height(screen)),
                 Constraint::Length(1),
-                Constraint::Length(4),
+                Constraint::Length(if title_height(screen) > 4 { 4 } else { 1 }),
                 Constraint::Ratio(2,5),
                 Constraint::Ratio(1,5),
             ])
@@ -148,6 +177,117 @@ impl Model for MenuScreen {
 
         render_title_block(frame, menu_layout[0]);
         render_sub_title_block(frame, menu_layout[1]);
-        self.render_menu_body(frame, menu_layout[3]);
+        self.menu.render(frame, menu_layout[3]);
+        self.menu.render_description(frame, menu_layout[4]);
     }
-}
\ No newline at end of file
+
+    fn revealed(&mut self, can_resume: bool) {
+        self.enable_continue(can_resume);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyCode;
+    use crate::testing::{Harness, ScriptedEvents};
+
+    #[test]
+    fn cursor_wraps_around_at_the_edges() {
+        let mut harness = Harness::new();
+        harness.run(ScriptedEvents::new().key(KeyCode::Up));
+        harness.assert_contains("> Settings");
+        harness.run(ScriptedEvents::new().key(KeyCode::Up).key(KeyCode::Down).type_text("j"));
+        harness.assert_contains("> Continue");
+    }
+
+    #[test]
+    fn hotkeys_open_entries() {
+        let mut harness = Harness::new();
+        harness.run(ScriptedEvents::new().type_text("s"));
+        harness.assert_contains("Number of Decks");
+    }
+
+    #[test]
+    fn settings_entry_opens_settings() {
+        let mut harness = Harness::new();
+        harness.run(ScriptedEvents::new().type_text("jjjjjjj").key(KeyCode::Enter));
+        harness.assert_contains("Number of Decks");
+    }
+
+    #[test]
+    fn play_asks_for_the_player_name() {
+        let mut harness = Harness::new();
+        harness.run(
+            ScriptedEvents::new()
+                .key(KeyCode::Enter)
+                .key(KeyCode::Backspace)
+                .key(KeyCode::Backspace)
+                .type_text("ill")
+                .key(KeyCode::Enter),
+        );
+        harness.assert_contains(" Jaill ");
+    }
+
+    #[test]
+    fn continue_without_a_game_shows_a_toast() {
+        let mut harness = Harness::new();
+        harness.run(ScriptedEvents::new().key(KeyCode::Down).key(KeyCode::Enter));
+        harness.assert_contains("Nothing to continue yet");
+    }
+
+    #[test]
+    fn continue_resumes_the_game_left_with_esc() {
+        let mut harness = Harness::new();
+        harness.run(
+            ScriptedEvents::new()
+                .key(KeyCode::Enter)
+                .key(KeyCode::Enter)
+                .type_text("42")
+                .key(KeyCode::Esc)
+                .key(KeyCode::Down)
+                .key(KeyCode::Enter),
+        );
+        harness.assert_contains("BET: $42");
+    }
+
+    #[test]
+    fn continue_resumes_the_game_after_a_detour_through_settings() {
+        let mut harness = Harness::new();
+        harness.run(
+            ScriptedEvents::new()
+                .key(KeyCode::Enter)
+                .key(KeyCode::Enter)
+                .type_text("42")
+                .key(KeyCode::Esc)
+                .type_text("s")
+                .key(KeyCode::Esc)
+                .type_text("c"),
+        );
+        harness.assert_contains("BET: $42");
+    }
+
+    #[test]
+    fn small_terminals_get_the_compact_title() {
+        let mut harness = Harness::with_size(80, 24);
+        harness.run(ScriptedEvents::new());
+        harness.assert_contains("JACK'S BLACKJACK");
+        harness.assert_contains("Settings");
+    }
+
+    #[test]
+    fn tiny_terminals_are_asked_to_grow() {
+        let mut harness = Harness::with_size(40, 12);
+        harness.run(ScriptedEvents::new().key(KeyCode::Enter));
+        harness.assert_contains("Terminal too small");
+        harness.assert_not_contains("Player name");
+    }
+
+    #[test]
+    fn clicking_an_entry_opens_it() {
+        let mut harness = Harness::new();
+        harness.run(ScriptedEvents::new());
+        let settings = harness.position_of("Settings").unwrap();
+        harness.run(ScriptedEvents::new().click(settings));
+        harness.assert_contains("Number of Decks");
+    }
+}
@@ -0,0 +1,298 @@
+use ratatui::crossterm::event::KeyCode;
+use ratatui::Frame;
+use ratatui::layout::{Alignment, Position, Rect};
+use ratatui::prelude::{Line, Span, Stylize};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use crate::event::AppEvent;
+use crate::keybindings::{bindings, Action, Context};
+use crate::theme::{theme, Slot};
+use crate::ui::centered_x;
+
+/// Put in front of the selected item
+const SELECTION_MARKER: &str = "> ";
+
+/// One entry of a `Menu`, carrying the value handed back when it is picked
+pub struct MenuItem<T> {
+    pub value: T,
+    label: String,
+    hotkey: Option<char>,
+    description: Option<String>,
+    enabled: bool,
+}
+
+impl<T> MenuItem<T> {
+    pub fn new(value: T, label: impl Into<String>) -> MenuItem<T> {
+        MenuItem {
+            value,
+            label: label.into(),
+            hotkey: None,
+            description: None,
+            enabled: true,
+        }
+    }
+
+    /// A key that picks the item straight away. Keys bound to menu actions win over it.
+    pub fn hotkey(mut self, hotkey: char) -> MenuItem<T> {
+        self.hotkey = Some(hotkey.to_ascii_lowercase());
+        self
+    }
+
+    /// Shown under the menu while the item is selected
+    pub fn description(mut self, description: impl Into<String>) -> MenuItem<T> {
+        self.description = Some(description.into());
+        self
+    }
+
+    pub fn set_label(&mut self, label: impl Into<String>) {
+        self.label = label.into();
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+}
+
+/// What a `Menu` made of an event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuEvent<T> {
+    /// Not meant for the menu, the screen can handle it
+    Ignored,
+    /// The selection moved
+    Moved,
+    /// An item was picked with Select or its hotkey
+    Activated(T),
+    /// An item was clicked, along with the character of its label that was hit
+    Clicked(T, Option<char>),
+    /// A disabled item was picked, nothing happened
+    Disabled(T),
+}
+
+/// A vertical list of items, centred, with a blank line above each one when
+/// there is room, scrolled when even that doesn't fit. Handles keys, hotkeys,
+/// clicks and the scroll wheel, and wraps around at both ends.
+pub struct Menu<T> {
+    items: Vec<MenuItem<T>>,
+    selected: usize,
+    /// Where the menu was last drawn, for mouse clicks
+    rect: Rect,
+    /// First item shown, when there are more items than lines to draw them on
+    first: usize,
+}
+
+impl<T: Copy> Menu<T> {
+    pub fn new(items: Vec<MenuItem<T>>) -> Menu<T> {
+        Menu {
+            items,
+            selected: 0,
+            rect: Rect::default(),
+            first: 0,
+        }
+    }
+
+    pub fn selected(&self) -> Option<T> {
+        self.items.get(self.selected).map(|item| item.value)
+    }
+
+    pub fn items_mut(&mut self) -> impl Iterator<Item = &mut MenuItem<T>> {
+        self.items.iter_mut()
+    }
+
+    /// Moves the selection by `step` items, wrapping around at either end
+    pub fn move_by(&mut self, step: isize) {
+        if self.items.is_empty() {
+            return;
+        }
+        let len = self.items.len() as isize;
+        self.selected = (self.selected as isize + step).rem_euclid(len) as usize;
+    }
+
+    pub fn handle(&mut self, event: &AppEvent) -> MenuEvent<T> {
+        if let Some(position) = event.left_click() {
+            let Some((index, column)) = self.hit(position) else {
+                return MenuEvent::Ignored;
+            };
+            // The selection marker is not part of the label
+            let marker = if index == self.selected { SELECTION_MARKER.len() } else { 0 };
+            let hit = column.checked_sub(marker).and_then(|column| self.items[index].label.chars().nth(column));
+            self.selected = index;
+            return self.pick(|value| MenuEvent::Clicked(value, hit));
+        }
+        if let Some(scroll) = event.scroll() {
+            self.move_by(-scroll as isize);
+            return MenuEvent::Moved;
+        }
+        let AppEvent::Key(key) = event else {
+            return MenuEvent::Ignored;
+        };
+        match bindings().action(Context::Menu, key) {
+            Some(Action::Up) => {
+                self.move_by(-1);
+                return MenuEvent::Moved;
+            }
+            Some(Action::Down) => {
+                self.move_by(1);
+                return MenuEvent::Moved;
+            }
+            Some(Action::Select) => return self.pick(MenuEvent::Activated),
+            Some(_) => return MenuEvent::Ignored,
+            None => {}
+        }
+        let KeyCode::Char(ch) = key.code else {
+            return MenuEvent::Ignored;
+        };
+        let hotkey = ch.to_ascii_lowercase();
+        let Some(index) = self.items.iter().position(|item| item.hotkey == Some(hotkey)) else {
+            return MenuEvent::Ignored;
+        };
+        self.selected = index;
+        self.pick(MenuEvent::Activated)
+    }
+
+    /// The selected item as `event`, or `MenuEvent::Disabled` if it can't be picked
+    fn pick(&self, event: impl FnOnce(T) -> MenuEvent<T>) -> MenuEvent<T> {
+        match self.items.get(self.selected) {
+            Some(item) if item.enabled => event(item.value),
+            Some(item) => MenuEvent::Disabled(item.value),
+            None => MenuEvent::Ignored,
+        }
+    }
+
+    /// Index of the item drawn at `position`, and the column of its line that was hit
+    fn hit(&self, position: Position) -> Option<(usize, usize)> {
+        if !self.rect.contains(position) {
+            return None;
+        }
+        let line = (position.y - self.rect.y) as usize;
+        let index = match self.is_spaced() {
+            true if line.is_multiple_of(2) => return None,
+            true => line / 2,
+            false => line + self.first,
+        };
+        if index >= self.items.len() {
+            return None;
+        }
+        let width = self.item_text(index).chars().count() as u16;
+        let column = position.x.saturating_sub(centered_x(self.rect, width)) as usize;
+        Some((index, column))
+    }
+
+    /// Items get a blank line above them, unless the menu was drawn too short for it
+    fn is_spaced(&self) -> bool {
+        self.rect.height as usize >= self.items.len() * 2
+    }
+
+    fn item_text(&self, index: usize) -> String {
+        let prefix = if index == self.selected { SELECTION_MARKER } else { "" };
+        format!("{}{}", prefix, self.items[index].label)
+    }
+
+    /// The item's line, with its hotkey underlined
+    fn item_line(&self, index: usize) -> Line<'static> {
+        let item = &self.items[index];
+        let style = match (item.enabled, index == self.selected) {
+            (false, _) => Style::default().fg(theme().color(Slot::Disabled)),
+            (true, true) => theme().highlight(),
+            (true, false) => Style::default(),
+        };
+        let text = self.item_text(index);
+        let hotkey_at = item.hotkey.and_then(|hotkey| {
+            text.char_indices()
+                .skip(text.len() - item.label.len())
+                .find(|(_, ch)| ch.to_ascii_lowercase() == hotkey)
+                .map(|(at, ch)| (at, ch.len_utf8()))
+        });
+        let spans = match hotkey_at {
+            Some((at, len)) => vec![
+                Span::raw(text[..at].to_string()),
+                Span::styled(text[at..at + len].to_string(), Style::default().add_modifier(Modifier::UNDERLINED)),
+                Span::raw(text[at + len..].to_string()),
+            ],
+            None => vec![Span::raw(text)],
+        };
+        Line::from(spans).style(style)
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, rect: Rect) {
+        self.rect = rect;
+        // Scrolls just far enough to keep the selection in view
+        let height = (rect.height as usize).max(1);
+        self.first = match self.is_spaced() {
+            true => 0,
+            false if self.selected < self.first => self.selected,
+            false if self.selected >= self.first + height => self.selected + 1 - height,
+            false => self.first.min(self.items.len().saturating_sub(height)),
+        };
+        let mut body: Vec<Line<'_>> = vec![];
+        for index in self.first..self.items.len() {
+            if self.is_spaced() {
+                body.push(Line::from(""));
+            }
+            body.push(self.item_line(index));
+        }
+
+        let menu = Paragraph::new(body)
+            .bold()
+            .alignment(Alignment::Center)
+            .block(Block::default());
+        frame.render_widget(menu, rect);
+    }
+
+    /// Draws the selected item's description, if it has one
+    pub fn render_description(&self, frame: &mut Frame, rect: Rect) {
+        let Some(description) = self.items.get(self.selected).and_then(|item| item.description.as_deref()) else {
+            return;
+        };
+        let paragraph = Paragraph::new(description.to_string())
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(theme().color(Slot::Disabled)).add_modifier(Modifier::ITALIC));
+        frame.render_widget(paragraph, rect);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use crate::event::AppEvent;
+    use super::{Menu, MenuEvent, MenuItem};
+
+    fn press(code: KeyCode) -> AppEvent {
+        AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn menu() -> Menu<u8> {
+        let mut items = vec![
+            MenuItem::new(1, "One").hotkey('o'),
+            MenuItem::new(2, "Two").hotkey('t'),
+            MenuItem::new(3, "Three"),
+        ];
+        items[1].set_enabled(false);
+        Menu::new(items)
+    }
+
+    #[test]
+    fn navigation_wraps_around() {
+        let mut menu = menu();
+        assert_eq!(menu.handle(&press(KeyCode::Up)), MenuEvent::Moved);
+        assert_eq!(menu.selected(), Some(3));
+        menu.handle(&press(KeyCode::Down));
+        assert_eq!(menu.selected(), Some(1));
+    }
+
+    #[test]
+    fn hotkeys_pick_items_unless_disabled() {
+        let mut menu = menu();
+        assert_eq!(menu.handle(&press(KeyCode::Char('T'))), MenuEvent::Disabled(2));
+        assert_eq!(menu.handle(&press(KeyCode::Char('o'))), MenuEvent::Activated(1));
+        assert_eq!(menu.handle(&press(KeyCode::Char('x'))), MenuEvent::Ignored);
+    }
+
+    #[test]
+    fn empty_menus_do_nothing() {
+        let mut menu: Menu<u8> = Menu::new(vec![]);
+        menu.move_by(-1);
+        assert_eq!(menu.handle(&press(KeyCode::Enter)), MenuEvent::Ignored);
+        assert_eq!(menu.selected(), None);
+    }
+}
@@ -1 +1,2 @@
-pub mod menu_screen;
\ No newline at end of file
+pub mod menu_screen;
+pub mod menu_widget;
\ No newline at end of file
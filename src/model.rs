@@ -1,8 +1,13 @@
 use std::io;
 use ratatui::Frame;
-use blackjack_engine::game_settings::GameSettings;
+use crate::event::AppEvent;
+use crate::game::rules::HouseRules;
+use crate::keybindings::Context;
+use crate::navigation::Navigation;
+use crate::overlay::help::HelpOverlay;
+use crate::overlay::modal::Modal;
 
-#[derive(PartialEq, Debug)]
+#[derive(Debug)]
 pub enum ModelResponse {
     /// Check for another update from the screen model
     NoOp,
@@ -10,27 +15,43 @@ pub enum ModelResponse {
     Refresh,
     /// Exit the application
     Exit,
-    /// Switch to Settings Screen
-    NavToSettings,
-    NavToHighScores,
-    NavToTutorial,
-    NavToMainMenu,
-    NavToGame,
-    /// QuitGame
-    QuitGame,
+    /// Move to another screen
+    Navigate(Navigation),
+    /// Show a dialog over the screen, the answer comes back as `AppEvent::ModalClosed`
+    OpenModal(Modal),
+    /// Show a short notification over the screen
+    Toast(String),
+    /// Show a scrollable page over the screen, closed like the help
+    OpenPage(HelpOverlay),
 }
 
 pub trait Model {
-    fn update(&mut self) -> io::Result<ModelResponse>;
+    /// Called by main program loop with every event (key press, tick, resize...).
+    /// Screens must not read from the terminal themselves.
+    fn update(&mut self, event: &AppEvent) -> io::Result<ModelResponse>;
 
     /// Called by main program loop to refresh/redraw the current screen
     fn ui(&mut self, frame: &mut Frame);
+
+    /// What the help key shows on this screen
+    fn help(&self) -> HelpOverlay {
+        HelpOverlay::new(Context::Menu, &HouseRules::default())
+    }
+
+    /// Whether the screen is kept when it is left, for `Navigation::Resume` to reopen
+    fn resumable(&self) -> bool {
+        false
+    }
+
+    /// Called when the screens over this one were closed. `can_resume` tells
+    /// whether a table was left that `Navigation::Resume` would reopen.
+    fn revealed(&mut self, _can_resume: bool) {}
 }
 
 // Note:
 // The general idea of this application is simple, we have a loop. That loop
-// only knows about one variable, the model. It asks the model to update itself,
-// then it asks the model to mutate the given frame.
+// only knows about one variable, the model. It hands the model the next event
+// and asks it to update itself, then it asks the model to mutate the given frame.
 //
 // This allows use to create a loop that functions like a statemachine, where
 // the transition logic is separate from the loop logic.
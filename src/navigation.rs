@@ -0,0 +1,150 @@
+use std::fmt;
+use crate::model::Model;
+
+/// How to move between screens. Screens build the screen they want to open
+/// themselves, so anything it needs is passed straight to its constructor.
+pub enum Navigation {
+    /// Open a screen on top of the current one
+    Push(Box<dyn Model>),
+    /// Go back to the screen underneath. A table left this way can be reopened with `Resume`.
+    Pop,
+    /// Swap the current screen for another one
+    Replace(Box<dyn Model>),
+    /// Reopen the table that was left last
+    Resume,
+    /// Go back to the first screen
+    PopToRoot,
+}
+
+impl fmt::Debug for Navigation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Navigation::Push(_) => write!(f, "Push(..)"),
+            Navigation::Pop => write!(f, "Pop"),
+            Navigation::Replace(_) => write!(f, "Replace(..)"),
+            Navigation::Resume => write!(f, "Resume"),
+            Navigation::PopToRoot => write!(f, "PopToRoot"),
+        }
+    }
+}
+
+/// Stack of open screens. Only the top one is updated and drawn, the ones
+/// underneath keep their state until the user comes back to them. A table
+/// that is left is kept aside, whatever is opened after it, until it is
+/// resumed or a new table takes its place.
+pub struct Navigator {
+    stack: Vec<Box<dyn Model>>,
+    /// The table left last, see `Model::resumable`
+    suspended: Option<Box<dyn Model>>,
+}
+
+impl Navigator {
+    pub fn new(root: Box<dyn Model>) -> Navigator {
+        Navigator {
+            stack: vec![root],
+            suspended: None,
+        }
+    }
+
+    pub fn current(&mut self) -> &mut dyn Model {
+        self.stack.last_mut().expect("the root screen is never popped").as_mut()
+    }
+
+    /// Returns false if there was nowhere to go
+    pub fn navigate(&mut self, navigation: Navigation) -> bool {
+        match navigation {
+            Navigation::Push(screen) => {
+                // A new table replaces the one left earlier
+                if screen.resumable() {
+                    self.suspended = None;
+                }
+                self.stack.push(screen);
+            }
+            Navigation::Pop => {
+                self.pop();
+                self.reveal();
+            }
+            Navigation::Replace(screen) => {
+                if self.stack.len() > 1 {
+                    self.stack.pop();
+                    self.stack.push(screen);
+                } else {
+                    self.stack[0] = screen;
+                }
+            }
+            Navigation::Resume => {
+                let Some(screen) = self.suspended.take() else {
+                    return false;
+                };
+                self.stack.push(screen);
+            }
+            Navigation::PopToRoot => {
+                while self.stack.len() > 1 {
+                    self.pop();
+                }
+                self.reveal();
+            }
+        }
+        true
+    }
+
+    fn pop(&mut self) {
+        if self.stack.len() > 1 {
+            match self.stack.pop() {
+                Some(screen) if screen.resumable() => self.suspended = Some(screen),
+                _ => {}
+            }
+        }
+    }
+
+    /// Tells the screen back on top whether there is a table to resume
+    fn reveal(&mut self) {
+        let can_resume = self.suspended.is_some();
+        self.current().revealed(can_resume);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::Frame;
+    use crate::event::AppEvent;
+    use crate::model::{Model, ModelResponse};
+    use super::{Navigation, Navigator};
+
+    /// A screen that only says whether it is a table
+    struct Screen {
+        table: bool,
+    }
+
+    impl Model for Screen {
+        fn update(&mut self, _event: &AppEvent) -> std::io::Result<ModelResponse> {
+            Ok(ModelResponse::NoOp)
+        }
+
+        fn ui(&mut self, _frame: &mut Frame) {}
+
+        fn resumable(&self) -> bool {
+            self.table
+        }
+    }
+
+    fn screen(table: bool) -> Box<dyn Model> {
+        Box::new(Screen { table })
+    }
+
+    #[test]
+    fn only_a_table_that_was_left_can_be_resumed() {
+        let mut navigator = Navigator::new(screen(false));
+        navigator.navigate(Navigation::Push(screen(true)));
+        navigator.navigate(Navigation::Pop);
+        navigator.navigate(Navigation::Push(screen(false)));
+        navigator.navigate(Navigation::Pop);
+        assert!(navigator.navigate(Navigation::Resume));
+        assert!(navigator.current().resumable());
+
+        // The table gave way to an error screen, there is nothing to go back to
+        navigator.navigate(Navigation::Replace(screen(false)));
+        navigator.navigate(Navigation::PopToRoot);
+        assert!(!navigator.navigate(Navigation::Resume));
+    }
+}
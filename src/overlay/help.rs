@@ -0,0 +1,111 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::prelude::{Line, Stylize};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use crate::game::rules::HouseRules;
+use crate::keybindings::{bindings, key_label, Action, Context};
+use crate::ui::centered_rect;
+
+const GLOSSARY: [(&str, &str); 13] = [
+    ("Hard hand", "A hand without an ace, or where every ace counts as 1"),
+    ("Soft hand", "A hand with an ace counted as 11, it can't bust on the next card"),
+    ("Bust", "Going over 21, the bet is lost whatever the dealer does"),
+    ("Push", "Tying with the dealer, the bet is returned"),
+    ("DAS", "Double After Split, doubling is allowed on hands made by splitting"),
+    ("H17", "The dealer Hits a soft 17"),
+    ("S17", "The dealer Stands on every 17, soft or hard"),
+    ("Basic strategy", "The best play for every hand against each dealer up card, ignoring the count"),
+    ("Penetration", "How much of the shoe is dealt before it is reshuffled"),
+    ("Running count", "The card counting tags of every card seen since the shuffle, added up"),
+    ("True count", "The running count per deck left in the shoe"),
+    ("Bet spread", "The smallest bet to the largest in units, raised as the true count climbs"),
+    ("Index play", "A play that changes from basic strategy once the true count reaches its index"),
+];
+
+/// Scrollable list of the controls for the current screen, the house rules,
+/// the payouts and a short glossary. Opened from any screen with the help key.
+#[derive(Clone, Debug)]
+pub struct HelpOverlay {
+    title: String,
+    lines: Vec<Line<'static>>,
+    scroll: u16,
+}
+
+impl HelpOverlay {
+    pub fn new(context: Context, rules: &HouseRules) -> HelpOverlay {
+        let mut lines = vec![Line::from("Controls").bold().underlined()];
+        if context == Context::Betting {
+            lines.push(Line::from("  0-9: Type in the bet"));
+            lines.push(Line::from(format!("  {}: Erase the last digit", key_label(KeyCode::Backspace))));
+        }
+        for action in context.actions() {
+            if action == Action::Surrender && !rules.surrender {
+                continue;
+            }
+            let keys = bindings().keys(action).iter().map(|code| key_label(*code)).collect::<Vec<_>>();
+            lines.push(Line::from(format!("  {}: {}", keys.join("/"), action.label())));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("House rules").bold().underlined());
+        lines.extend(rules.describe().into_iter().map(|rule| Line::from(format!("  {}", rule))));
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("Payouts").bold().underlined());
+        for (outcome, payout) in rules.payouts() {
+            lines.push(Line::from(format!("  {}: {}", outcome, payout)));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("Glossary").bold().underlined());
+        for (term, meaning) in GLOSSARY {
+            lines.push(Line::from(format!("  {}: {}", term, meaning)));
+        }
+
+        HelpOverlay::page("Help", lines)
+    }
+
+    /// Any other text shown and scrolled the same way, e.g. a report
+    pub fn page(title: &str, lines: Vec<Line<'static>>) -> HelpOverlay {
+        HelpOverlay {
+            title: format!(" {} ", title),
+            lines,
+            scroll: 0,
+        }
+    }
+
+    /// Scrolls with the menu up and down keys, returns true once the help was closed
+    pub fn handle_key(&mut self, key: &KeyEvent) -> bool {
+        let last_line = self.lines.len().saturating_sub(1) as u16;
+        match key.code {
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = (self.scroll + 10).min(last_line),
+            _ => match bindings().action(Context::Menu, key) {
+                Some(Action::Up) => self.scroll = self.scroll.saturating_sub(1),
+                Some(Action::Down) => self.scroll = (self.scroll + 1).min(last_line),
+                Some(Action::Back | Action::Help | Action::Quit | Action::Select) => return true,
+                _ => {}
+            },
+        }
+        false
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let screen = frame.area();
+        let area = centered_rect(screen, 76, screen.height.saturating_sub(4));
+        // Stop once the last line reaches the bottom of the dialog
+        let visible = area.height.saturating_sub(2);
+        let scroll = self.scroll.min((self.lines.len() as u16).saturating_sub(visible));
+
+        let block = Block::default()
+            .borders(Borders::all())
+            .title(self.title.as_str())
+            .title_bottom(Line::from(" ↑/↓ Scroll   Esc Close ").centered());
+        let help = Paragraph::new(self.lines.clone())
+            .wrap(Wrap { trim: false })
+            .scroll((scroll, 0))
+            .block(block);
+        frame.render_widget(Clear, area);
+        frame.render_widget(help, area);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod help;
+pub mod modal;
+pub mod toast;
+pub mod overlay_layer;
\ No newline at end of file
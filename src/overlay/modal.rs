@@ -0,0 +1,157 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::layout::Alignment;
+use ratatui::prelude::{Line, Stylize};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use crate::keybindings::{bindings, Action, Context};
+use crate::theme::theme;
+use crate::ui::centered_rect;
+
+/// What the user did with a modal. Sent back to the screen that opened it
+/// as an `AppEvent::ModalClosed`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModalResult {
+    Confirmed,
+    Cancelled,
+    /// The text typed into a text input modal
+    Text(String),
+    /// Index of the option picked in a choice modal
+    Choice(usize),
+}
+
+#[derive(Clone, Debug)]
+enum ModalKind {
+    Confirm,
+    TextInput { value: String },
+    Choice { options: Vec<String>, selected: usize },
+}
+
+/// A centered dialog drawn over the current screen. While it is open it
+/// receives every key press, mapped through the bindings of `Context::Modal`.
+#[derive(Clone, Debug)]
+pub struct Modal {
+    /// Lets the screen tell its dialogs apart when the result comes back
+    pub id: &'static str,
+    title: String,
+    message: String,
+    kind: ModalKind,
+}
+
+impl Modal {
+    /// A yes/no question
+    pub fn confirm(id: &'static str, title: &str, message: &str) -> Modal {
+        Modal::new(id, title, message, ModalKind::Confirm)
+    }
+
+    /// A single line text field, pre-filled with `value`
+    pub fn text_input(id: &'static str, title: &str, message: &str, value: &str) -> Modal {
+        Modal::new(id, title, message, ModalKind::TextInput { value: value.to_string() })
+    }
+
+    /// A list of options to pick one from
+    pub fn choice(id: &'static str, title: &str, message: &str, options: Vec<String>) -> Modal {
+        Modal::new(id, title, message, ModalKind::Choice { options, selected: 0 })
+    }
+
+    fn new(id: &'static str, title: &str, message: &str, kind: ModalKind) -> Modal {
+        Modal {
+            id,
+            title: title.to_string(),
+            message: message.to_string(),
+            kind,
+        }
+    }
+
+    /// Returns the result once the key closed the modal
+    pub fn handle_key(&mut self, key: &KeyEvent) -> Option<ModalResult> {
+        // Characters are typed into a text field, whatever they are bound to
+        let action = match (&self.kind, key.code) {
+            (ModalKind::TextInput { .. }, KeyCode::Char(_)) => None,
+            _ => bindings().action(Context::Modal, key),
+        };
+        if action == Some(Action::Back) {
+            return Some(ModalResult::Cancelled);
+        }
+        match &mut self.kind {
+            ModalKind::Confirm => match action? {
+                Action::Select | Action::Confirm => Some(ModalResult::Confirmed),
+                Action::Decline => Some(ModalResult::Cancelled),
+                _ => None,
+            },
+            ModalKind::TextInput { value } => match (action, key.code) {
+                (Some(Action::Select), _) => Some(ModalResult::Text(value.clone())),
+                (_, KeyCode::Backspace) => {
+                    value.pop();
+                    None
+                }
+                (_, KeyCode::Char(ch)) => {
+                    value.push(ch);
+                    None
+                }
+                _ => None,
+            },
+            ModalKind::Choice { options, selected } => match action? {
+                Action::Select => Some(ModalResult::Choice(*selected)),
+                Action::Down => {
+                    *selected = (*selected + 1) % options.len();
+                    None
+                }
+                Action::Up => {
+                    *selected = (*selected + options.len() - 1) % options.len();
+                    None
+                }
+                _ => None,
+            },
+        }
+    }
+
+    /// The keys that work in this dialog, for its bottom border
+    fn hint(&self) -> String {
+        let keys = bindings();
+        let (select, cancel) = (keys.key_hint(Action::Select), keys.key_hint(Action::Back));
+        match self.kind {
+            ModalKind::Confirm => format!(
+                " {}/{} Confirm   {}/{} Cancel ",
+                keys.key_hint(Action::Confirm),
+                select,
+                keys.key_hint(Action::Decline),
+                cancel
+            ),
+            ModalKind::TextInput { .. } => format!(" {} Accept   {} Cancel ", select, cancel),
+            ModalKind::Choice { .. } => format!(" {} Select   {} Cancel ", select, cancel),
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let mut body: Vec<Line<'_>> = vec![Line::from(self.message.as_str()), Line::from("")];
+        match &self.kind {
+            ModalKind::Confirm => {}
+            ModalKind::TextInput { value } => body.push(Line::from(format!("> {}█", value)).bold()),
+            ModalKind::Choice { options, selected } => {
+                for (i, option) in options.iter().enumerate() {
+                    if i == *selected {
+                        body.push(Line::from(format!("> {}", option)).style(theme().highlight()).bold());
+                    } else {
+                        body.push(Line::from(option.as_str()));
+                    }
+                }
+            }
+        }
+
+        let width = 50;
+        // Borders, the body, a blank line and wrapped lines of the message
+        let height = body.len() as u16 + 3 + self.message.len() as u16 / (width - 4);
+        let area = centered_rect(frame.area(), width, height);
+
+        let block = Block::default()
+            .borders(Borders::all())
+            .title(format!(" {} ", self.title))
+            .title_bottom(Line::from(self.hint()).centered());
+        let dialog = Paragraph::new(body)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(block);
+        frame.render_widget(Clear, area);
+        frame.render_widget(dialog, area);
+    }
+}
@@ -0,0 +1,85 @@
+use ratatui::Frame;
+use crate::event::AppEvent;
+use crate::overlay::help::HelpOverlay;
+use crate::overlay::modal::Modal;
+use crate::overlay::toast::Toast;
+
+/// Dialogs and notifications drawn on top of whichever screen is current.
+/// Screens ask for them through `ModelResponse::OpenModal` and `ModelResponse::Toast`.
+#[derive(Default)]
+pub struct OverlayLayer {
+    modal: Option<Modal>,
+    help: Option<HelpOverlay>,
+    toasts: Vec<Toast>,
+}
+
+impl OverlayLayer {
+    pub fn new() -> OverlayLayer {
+        OverlayLayer::default()
+    }
+
+    /// Opens `modal`, replacing any modal that is already open
+    pub fn open(&mut self, modal: Modal) {
+        self.modal = Some(modal);
+    }
+
+    /// Shows `help` over the screen and any open modal until it is closed
+    pub fn open_help(&mut self, help: HelpOverlay) {
+        self.help = Some(help);
+    }
+
+    pub fn toast(&mut self, message: &str) {
+        self.toasts.push(Toast::new(message));
+    }
+
+    /// Ages the toasts by one tick, returns true if any of them went away
+    pub fn tick(&mut self) -> bool {
+        let before = self.toasts.len();
+        self.toasts.retain_mut(|toast| toast.tick());
+        self.toasts.len() != before
+    }
+
+    /// Gives the overlay first look at an event. While the help or a modal is
+    /// open it takes all keyboard and mouse input, and `None` is returned. Once
+    /// a modal closes, its result is handed back as an `AppEvent::ModalClosed`
+    /// for the screen underneath. Every other event passes through untouched.
+    pub fn intercept(&mut self, event: AppEvent) -> Option<AppEvent> {
+        if let Some(help) = &mut self.help {
+            return match &event {
+                AppEvent::Key(key) => {
+                    if help.handle_key(key) {
+                        self.help = None;
+                    }
+                    None
+                }
+                AppEvent::Mouse(_) => None,
+                _ => Some(event),
+            };
+        }
+        let Some(modal) = &mut self.modal else {
+            return Some(event);
+        };
+        match &event {
+            AppEvent::Key(key) => {
+                let result = modal.handle_key(key)?;
+                let id = modal.id;
+                self.modal = None;
+                Some(AppEvent::ModalClosed { id, result })
+            }
+            AppEvent::Mouse(_) => None,
+            _ => Some(event),
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        for (slot, toast) in self.toasts.iter().enumerate() {
+            toast.render(frame, slot as u16);
+        }
+        if let Some(modal) = &self.modal {
+            modal.render(frame);
+        }
+        if let Some(help) = &self.help {
+            help.render(frame);
+        }
+    }
+}
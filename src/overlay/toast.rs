@@ -0,0 +1,46 @@
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+
+/// How long a toast stays up, in ticks (~3 seconds)
+const TOAST_TICKS: u16 = 90;
+
+/// A short notification shown in the top right corner that goes away on its own
+#[derive(Clone, Debug)]
+pub struct Toast {
+    message: String,
+    ticks_left: u16,
+}
+
+impl Toast {
+    pub fn new(message: &str) -> Toast {
+        Toast {
+            message: message.to_string(),
+            ticks_left: TOAST_TICKS,
+        }
+    }
+
+    /// Counts down one tick, returns false once the toast has expired
+    pub fn tick(&mut self) -> bool {
+        self.ticks_left = self.ticks_left.saturating_sub(1);
+        self.ticks_left > 0
+    }
+
+    /// Draws the toast as the `slot`th one from the top
+    pub fn render(&self, frame: &mut Frame, slot: u16) {
+        let screen = frame.area();
+        let width = (self.message.chars().count() as u16 + 4).min(screen.width);
+        let area = Rect {
+            x: screen.x + screen.width.saturating_sub(width + 1),
+            y: screen.y + 1 + slot * 3,
+            width,
+            height: 3,
+        }
+        .intersection(screen);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(self.message.as_str()).block(Block::default().borders(Borders::all())),
+            area,
+        );
+    }
+}
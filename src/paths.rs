@@ -0,0 +1,25 @@
+use std::env;
+use std::path::PathBuf;
+
+const APP_DIR_NAME: &str = "blackjack-engine-tui";
+
+/// Directory holding everything the app writes to disk (config, logs, saves).
+///
+/// `BLACKJACK_TUI_HOME` wins if set, then `$XDG_CONFIG_HOME`, then `~/.config`.
+/// Falls back to the working directory when none of them are available.
+pub fn app_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("BLACKJACK_TUI_HOME") {
+        return PathBuf::from(dir);
+    }
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME") {
+        return PathBuf::from(dir).join(APP_DIR_NAME);
+    }
+    if let Some(home) = env::var_os("HOME") {
+        return PathBuf::from(home).join(".config").join(APP_DIR_NAME);
+    }
+    PathBuf::from(".")
+}
+
+pub fn crash_log_path() -> PathBuf {
+    app_dir().join("crash.log")
+}
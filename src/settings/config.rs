@@ -0,0 +1,514 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::game::animation::AnimationSpeed;
+use crate::game::bet_ramp::BetRamp;
+use crate::game::count::CountSystem;
+use crate::game::strategy::HintMode;
+use crate::paths::app_dir;
+use crate::rng::Rng;
+
+/// The settings, shared between the screens that read them and the settings screen
+pub type SharedSettings = Rc<RefCell<Settings>>;
+
+/// Every setting there is. Each one is described by its `SettingDef`, the
+/// settings screen and the config file are built from those.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingKey {
+    Decks,
+    Players,
+    AnimationSpeed,
+    DefaultBet,
+    ConfirmQuit,
+    PlayerName,
+    StrategyHints,
+    CountSystem,
+    DealerSpeed,
+    BetSpread,
+    BetUnit,
+    CutCard,
+    FlashInterval,
+    FlashCards,
+}
+
+impl SettingKey {
+    pub const ALL: [SettingKey; 14] = [
+        SettingKey::Decks,
+        SettingKey::Players,
+        SettingKey::AnimationSpeed,
+        SettingKey::DefaultBet,
+        SettingKey::ConfirmQuit,
+        SettingKey::PlayerName,
+        SettingKey::StrategyHints,
+        SettingKey::CountSystem,
+        SettingKey::DealerSpeed,
+        SettingKey::BetSpread,
+        SettingKey::BetUnit,
+        SettingKey::CutCard,
+        SettingKey::FlashInterval,
+        SettingKey::FlashCards,
+    ];
+
+    pub fn definition(&self) -> SettingDef {
+        match self {
+            SettingKey::Decks => SettingDef {
+                label: "Number of Decks",
+                description: "Decks shuffled together into the shoe",
+                kind: SettingKind::Integer { min: 1, max: 8, step: 1 },
+                default: SettingValue::Integer(6),
+            },
+            SettingKey::Players => SettingDef {
+                label: "# of Players",
+                description: "Seats taken at the table",
+                kind: SettingKind::Integer { min: 1, max: 7, step: 1 },
+                default: SettingValue::Integer(1), //TODO: Default should be a full table
+            },
+            SettingKey::AnimationSpeed => SettingDef {
+                label: "Animation Speed",
+                description: "How fast cards move around the table",
+                kind: SettingKind::Choice { options: &["Off", "Slow", "Normal", "Fast"] },
+                default: SettingValue::Choice(2),
+            },
+            SettingKey::DefaultBet => SettingDef {
+                label: "Default Bet",
+                description: "Already typed in when sitting down at a table",
+                kind: SettingKind::Money { min: 0.0, max: 10_000.0, step: 5.0 },
+                default: SettingValue::Money(0.0),
+            },
+            SettingKey::ConfirmQuit => SettingDef {
+                label: "Confirm Quit Mid-Hand",
+                description: "Ask first when quitting would forfeit the hand in play",
+                kind: SettingKind::Toggle,
+                default: SettingValue::Toggle(true),
+            },
+            SettingKey::PlayerName => SettingDef {
+                label: "Player Name",
+                description: "Suggested when sitting down at a new table",
+                kind: SettingKind::Text { max_len: 16 },
+                default: SettingValue::Text("Jack".to_string()),
+            },
+            SettingKey::StrategyHints => SettingDef {
+                label: "Strategy Hints",
+                description: "When the basic strategy play is shown at the table",
+                kind: SettingKind::Choice { options: &["Off", "On Request", "Always"] },
+                default: SettingValue::Choice(0),
+            },
+            SettingKey::CountSystem => SettingDef {
+                label: "Count Panel",
+                description: "Card counting system kept in the stats area",
+                kind: SettingKind::Choice { options: &["Off", "Hi-Lo", "KO", "Omega II", "Zen"] },
+                default: SettingValue::Choice(0),
+            },
+            SettingKey::DealerSpeed => SettingDef {
+                label: "Dealer Speed",
+                description: "How fast cards come out in count practice, faster is harder",
+                kind: SettingKind::Choice { options: &["Slow", "Normal", "Fast"] },
+                default: SettingValue::Choice(0),
+            },
+            SettingKey::BetSpread => SettingDef {
+                label: "Bet Spread",
+                description: "Units bet from a neutral count up to a true count of +5, suggested while betting",
+                kind: SettingKind::Choice { options: &BET_SPREADS },
+                default: SettingValue::Choice(3),
+            },
+            SettingKey::BetUnit => SettingDef {
+                label: "Bet Unit",
+                description: "The smallest bet of the spread",
+                kind: SettingKind::Money { min: 5.0, max: 1_000.0, step: 5.0 },
+                default: SettingValue::Money(10.0),
+            },
+            SettingKey::CutCard => SettingDef {
+                label: "Cut Card",
+                description: "Placed by the house rules, or by you after every shuffle",
+                kind: SettingKind::Choice { options: &["House", "Player"] },
+                default: SettingValue::Choice(0),
+            },
+            SettingKey::FlashInterval => SettingDef {
+                label: "Flash Interval (ms)",
+                description: "How long each flash of the count flash drill stays up",
+                kind: SettingKind::Integer { min: 200, max: 3000, step: 100 },
+                default: SettingValue::Integer(1000),
+            },
+            SettingKey::FlashCards => SettingDef {
+                label: "Flash Cards At Once",
+                description: "Single cards, or pairs the way they land on the table",
+                kind: SettingKind::Integer { min: 1, max: 2, step: 1 },
+                default: SettingValue::Integer(1),
+            },
+        }
+    }
+}
+
+/// Options of the bet spread setting, option `n` tops out at `4 * n` units
+const BET_SPREADS: [&str; 6] = ["Off", "1-4", "1-8", "1-12", "1-16", "1-20"];
+
+/// What a setting is called, what it does, which values it takes and where it starts
+pub struct SettingDef {
+    pub label: &'static str,
+    pub description: &'static str,
+    pub kind: SettingKind,
+    pub default: SettingValue,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SettingKind {
+    Integer { min: i64, max: i64, step: i64 },
+    /// One of a fixed list of options, stored by name
+    Choice { options: &'static [&'static str] },
+    Toggle,
+    /// A dollar amount
+    Money { min: f64, max: f64, step: f64 },
+    Text { max_len: usize },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SettingValue {
+    Integer(i64),
+    /// Index into the options
+    Choice(usize),
+    Toggle(bool),
+    Money(f64),
+    Text(String),
+}
+
+impl SettingKind {
+    /// `value` moved one step up (positive `direction`) or down, kept within bounds.
+    /// Toggles flip either way, text can't be stepped.
+    pub fn step(&self, value: &SettingValue, direction: i8) -> SettingValue {
+        let direction = direction.signum();
+        match (self, value) {
+            (SettingKind::Integer { min, max, step }, SettingValue::Integer(current)) => {
+                SettingValue::Integer((current + step * direction as i64).clamp(*min, *max))
+            }
+            (SettingKind::Choice { options }, SettingValue::Choice(current)) => {
+                let index = (*current as isize + direction as isize).clamp(0, options.len() as isize - 1);
+                SettingValue::Choice(index as usize)
+            }
+            (SettingKind::Toggle, SettingValue::Toggle(current)) => SettingValue::Toggle(!current),
+            (SettingKind::Money { min, max, step }, SettingValue::Money(current)) => {
+                SettingValue::Money((current + step * direction as f64).clamp(*min, *max))
+            }
+            _ => value.clone(),
+        }
+    }
+
+    /// `value` the way the settings screen shows it
+    pub fn display(&self, value: &SettingValue) -> String {
+        match (self, value) {
+            (SettingKind::Choice { options }, SettingValue::Choice(index)) => options[*index].to_string(),
+            (_, SettingValue::Integer(value)) => value.to_string(),
+            (_, SettingValue::Toggle(true)) => "On".to_string(),
+            (_, SettingValue::Toggle(false)) => "Off".to_string(),
+            (_, SettingValue::Money(value)) => format!("${}", value),
+            (_, SettingValue::Text(value)) => value.clone(),
+            _ => String::new(),
+        }
+    }
+
+    fn json_of(&self, value: &SettingValue) -> Value {
+        match (self, value) {
+            (SettingKind::Choice { options }, SettingValue::Choice(index)) => Value::from(options[*index].to_ascii_lowercase().replace(' ', "_")),
+            (_, SettingValue::Integer(value)) => Value::from(*value),
+            (_, SettingValue::Toggle(value)) => Value::from(*value),
+            (_, SettingValue::Money(value)) => Value::from(*value),
+            (_, SettingValue::Text(value)) => Value::from(value.as_str()),
+            _ => Value::Null,
+        }
+    }
+
+    /// The value `json` holds, if it is one this kind of setting accepts
+    fn parse_json(&self, json: &Value) -> Option<SettingValue> {
+        match self {
+            SettingKind::Integer { min, max, .. } => {
+                json.as_i64().filter(|value| (min..=max).contains(&value)).map(SettingValue::Integer)
+            }
+            SettingKind::Choice { options } => {
+                let name = json.as_str()?;
+                options.iter().position(|option| option.replace(' ', "_").eq_ignore_ascii_case(name)).map(SettingValue::Choice)
+            }
+            SettingKind::Toggle => json.as_bool().map(SettingValue::Toggle),
+            SettingKind::Money { min, max, .. } => {
+                json.as_f64().filter(|value| (min..=max).contains(&value)).map(SettingValue::Money)
+            }
+            SettingKind::Text { max_len } => {
+                json.as_str().filter(|value| value.chars().count() <= *max_len).map(|value| SettingValue::Text(value.to_string()))
+            }
+        }
+    }
+}
+
+/// Current value of every setting, saved to `settings.json` after each change
+#[derive(Clone, Debug)]
+pub struct Settings {
+    values: BTreeMap<SettingKey, SettingValue>,
+    /// Where changes are saved, `None` keeps them in memory only
+    path: Option<PathBuf>,
+    /// Set from the command line, wins over the saved speed for this session
+    session_animation_speed: Option<AnimationSpeed>,
+    /// Deals the drills the same cards every session when set
+    session_seed: Option<u64>,
+}
+
+impl Default for Settings {
+    /// Every setting at its default, never saved
+    fn default() -> Settings {
+        Settings {
+            values: SettingKey::ALL.iter().map(|key| (*key, key.definition().default)).collect(),
+            path: None,
+            session_animation_speed: None,
+            session_seed: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn value(&self, key: SettingKey) -> &SettingValue {
+        &self.values[&key]
+    }
+
+    /// Sets `key` and saves the settings. Values of the wrong kind are ignored.
+    pub fn set(&mut self, key: SettingKey, value: SettingValue) -> io::Result<()> {
+        if std::mem::discriminant(&value) != std::mem::discriminant(&key.definition().default) {
+            return Ok(());
+        }
+        self.values.insert(key, value);
+        self.save()
+    }
+
+    pub fn reset(&mut self, key: SettingKey) -> io::Result<()> {
+        self.set(key, key.definition().default)
+    }
+
+    /// Where other progress files belong, `None` when the settings themselves aren't saved
+    pub fn storage_dir(&self) -> Option<PathBuf> {
+        self.path.as_ref().and_then(|path| path.parent()).map(PathBuf::from)
+    }
+
+    pub fn with_session_animation_speed(mut self, speed: AnimationSpeed) -> Settings {
+        self.session_animation_speed = Some(speed);
+        self
+    }
+
+    pub fn with_session_seed(mut self, seed: u64) -> Settings {
+        self.session_seed = Some(seed);
+        self
+    }
+
+    /// Where the drills draw their cards from, seeded from the clock unless the session fixed a seed
+    pub fn rng(&self) -> Rng {
+        self.session_seed.map(Rng::new).unwrap_or_else(Rng::from_time)
+    }
+
+    pub fn decks(&self) -> u8 {
+        match self.value(SettingKey::Decks) {
+            SettingValue::Integer(decks) => *decks as u8,
+            _ => 6,
+        }
+    }
+
+    pub fn animation_speed(&self) -> AnimationSpeed {
+        if let Some(speed) = self.session_animation_speed {
+            return speed;
+        }
+        match self.value(SettingKey::AnimationSpeed) {
+            SettingValue::Choice(0) => AnimationSpeed::Off,
+            SettingValue::Choice(1) => AnimationSpeed::Slow,
+            SettingValue::Choice(3) => AnimationSpeed::Fast,
+            _ => AnimationSpeed::Normal,
+        }
+    }
+
+    pub fn default_bet(&self) -> f64 {
+        match self.value(SettingKey::DefaultBet) {
+            SettingValue::Money(bet) => *bet,
+            _ => 0.0,
+        }
+    }
+
+    pub fn confirm_quit(&self) -> bool {
+        !matches!(self.value(SettingKey::ConfirmQuit), SettingValue::Toggle(false))
+    }
+
+    pub fn player_name(&self) -> &str {
+        match self.value(SettingKey::PlayerName) {
+            SettingValue::Text(name) => name,
+            _ => "Jack",
+        }
+    }
+
+    pub fn hint_mode(&self) -> HintMode {
+        match self.value(SettingKey::StrategyHints) {
+            SettingValue::Choice(index) => HintMode::ALL.get(*index).copied().unwrap_or(HintMode::Off),
+            _ => HintMode::Off,
+        }
+    }
+
+    /// Animation speed in count practice, the session override still wins
+    pub fn dealer_speed(&self) -> AnimationSpeed {
+        if let Some(speed) = self.session_animation_speed {
+            return speed;
+        }
+        match self.value(SettingKey::DealerSpeed) {
+            SettingValue::Choice(1) => AnimationSpeed::Normal,
+            SettingValue::Choice(2) => AnimationSpeed::Fast,
+            _ => AnimationSpeed::Slow,
+        }
+    }
+
+    /// Whether the player places the cut card after each shuffle
+    pub fn player_cuts(&self) -> bool {
+        matches!(self.value(SettingKey::CutCard), SettingValue::Choice(1))
+    }
+
+    /// Time each flash of the count flash drill stays up
+    pub fn flash_interval_ms(&self) -> u64 {
+        match self.value(SettingKey::FlashInterval) {
+            SettingValue::Integer(millis) => *millis as u64,
+            _ => 1000,
+        }
+    }
+
+    /// 1 for single cards, 2 for pairs
+    pub fn flash_cards(&self) -> usize {
+        match self.value(SettingKey::FlashCards) {
+            SettingValue::Integer(cards) => *cards as usize,
+            _ => 1,
+        }
+    }
+
+    /// `None` when the bet spread is off
+    pub fn bet_ramp(&self) -> Option<BetRamp> {
+        let SettingValue::Choice(index @ 1..) = self.value(SettingKey::BetSpread) else {
+            return None;
+        };
+        let unit = match self.value(SettingKey::BetUnit) {
+            SettingValue::Money(unit) => *unit,
+            _ => 10.0,
+        };
+        Some(BetRamp { unit, spread: *index as u32 * 4 })
+    }
+
+    /// `None` when the count panel is off
+    pub fn count_system(&self) -> Option<CountSystem> {
+        match self.value(SettingKey::CountSystem) {
+            SettingValue::Choice(index) => index.checked_sub(1).and_then(|index| CountSystem::ALL.get(index)).copied(),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let values = self
+            .values
+            .iter()
+            .map(|(key, value)| {
+                let name = serde_json::to_value(key).ok().and_then(|name| name.as_str().map(str::to_string));
+                (name.unwrap_or_default(), key.definition().kind.json_of(value))
+            })
+            .collect::<serde_json::Map<_, _>>();
+        Value::Object(values)
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents = serde_json::to_string_pretty(&self.to_json())?;
+        fs::write(path, contents)
+    }
+
+    /// Reads the values in `contents`. Settings missing from it, or that can't
+    /// be used, keep their defaults and are reported, the rest are kept.
+    fn parse(contents: &str) -> (Settings, Vec<String>) {
+        let mut settings = Settings::default();
+        let file: BTreeMap<String, Value> = match serde_json::from_str(contents) {
+            Ok(file) => file,
+            Err(err) => return (settings, vec![format!("settings.json: {}", err)]),
+        };
+
+        let mut problems = vec![];
+        for (name, json) in file {
+            let Ok(key) = serde_json::from_value::<SettingKey>(Value::from(name.as_str())) else {
+                problems.push(format!("settings.json: unknown setting {:?}", name));
+                continue;
+            };
+            match key.definition().kind.parse_json(&json) {
+                Some(value) => {
+                    settings.values.insert(key, value);
+                }
+                None => problems.push(format!("settings.json: {} can't be {}", name, json)),
+            }
+        }
+        (settings, problems)
+    }
+
+    /// Loads the settings file, keeping the defaults for anything missing or
+    /// unusable. Returns the settings and any problems worth telling the user about.
+    /// The file is only written again once the user changes a setting.
+    pub fn load() -> (Settings, Vec<String>) {
+        let (mut settings, problems) = match fs::read_to_string(settings_path()) {
+            Ok(contents) => Settings::parse(&contents),
+            Err(_) => (Settings::default(), vec![]),
+        };
+        settings.path = Some(settings_path());
+        (settings, problems)
+    }
+}
+
+pub fn settings_path() -> PathBuf {
+    app_dir().join("settings.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SettingKey, SettingKind, SettingValue, Settings};
+
+    #[test]
+    fn steps_stay_within_bounds() {
+        let decks = SettingKey::Decks.definition().kind;
+        assert_eq!(decks.step(&SettingValue::Integer(8), 1), SettingValue::Integer(8));
+        assert_eq!(decks.step(&SettingValue::Integer(1), -1), SettingValue::Integer(1));
+
+        let speed = SettingKind::Choice { options: &["Off", "On"] };
+        assert_eq!(speed.step(&SettingValue::Choice(0), -1), SettingValue::Choice(0));
+        assert_eq!(SettingKind::Toggle.step(&SettingValue::Toggle(true), -1), SettingValue::Toggle(false));
+    }
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut settings = Settings::default();
+        settings.set(SettingKey::AnimationSpeed, SettingValue::Choice(3)).unwrap();
+        settings.set(SettingKey::PlayerName, SettingValue::Text("Ada".to_string())).unwrap();
+
+        let (parsed, problems) = Settings::parse(&settings.to_json().to_string());
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(parsed.value(SettingKey::AnimationSpeed), &SettingValue::Choice(3));
+        assert_eq!(parsed.player_name(), "Ada");
+    }
+
+    #[test]
+    fn out_of_range_values_are_reported() {
+        let (settings, problems) = Settings::parse(r#"{ "decks": 40, "colour": "red", "confirm_quit": false }"#);
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert_eq!(settings.decks(), 6);
+    }
+
+    #[test]
+    fn one_bad_setting_keeps_the_good_ones() {
+        let (settings, problems) = Settings::parse(
+            r#"{ "decks": 2, "player_name": "Ada", "confirm_quit": false, "animation_speed": "warp" }"#,
+        );
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+        assert_eq!(settings.decks(), 2);
+        assert_eq!(settings.player_name(), "Ada");
+        assert!(!settings.confirm_quit());
+    }
+}
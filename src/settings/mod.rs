@@ -1 +1,2 @@
-pub mod settings_screen;
\ No newline at end of file
+pub mod config;
+pub mod settings_screen;
@@ -1,150 +1,155 @@
-use ratatui::crossterm::event;
-use ratatui::crossterm::event::{Event, KeyCode};
 use ratatui::Frame;
-use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
-use ratatui::prelude::{Color, Line, Stylize};
-use ratatui::widgets::{Block, Paragraph};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use crate::event::AppEvent;
+use crate::keybindings::{bindings, Action, Context};
+use crate::menu::menu_widget::{Menu, MenuEvent, MenuItem};
 use crate::model::{Model, ModelResponse};
-use crate::settings::settings_screen::SettingsMenuOption::{NumberOfDecks, NumberOfPlayers};
-use crate::ui::{render_border, render_sub_title_block, render_title_block, MenuNavigation};
+use crate::navigation::Navigation;
+use crate::overlay::modal::{Modal, ModalResult};
+use crate::settings::config::{SettingKey, SettingKind, SettingValue, SharedSettings};
+use crate::ui::{render_border, render_footer_hints, render_sub_title_block, render_title_block, title_height, FooterHint};
 
-enum SettingsMenuOption {
-    NumberOfDecks,
-    NumberOfPlayers
-}
-
-impl SettingsMenuOption {
-    pub fn to_string(&self) -> String {
-        match self {
-            NumberOfDecks => "Number of Decks".to_string(),
-            NumberOfPlayers => "# of Players".to_string()
-        }
-    }
-}
-
-const SETTINGS_ITEMS: [SettingsMenuOption; 2] = [
-    NumberOfDecks,
-    NumberOfPlayers
-];
+const TEXT_SETTING_MODAL: &str = "text_setting";
 
+/// Lists every setting with its current value. The items are built from the
+/// setting definitions, so new settings show up here without any extra work.
 pub struct SettingsScreen {
-    active_menu_index: i8,
-    number_of_decks_value: i8,
-    number_of_players_value: i8,
+    menu: Menu<SettingKey>,
+    settings: SharedSettings,
+    /// The text setting being edited in the open modal
+    editing: Option<SettingKey>,
 }
 
 impl SettingsScreen {
-    pub fn new() -> SettingsScreen {
-        SettingsScreen {
-            active_menu_index: 0,
-            number_of_decks_value: 6,
-            number_of_players_value: 1, //TODO: Default should be a full table
-        }
+    pub fn new(settings: SharedSettings) -> SettingsScreen {
+        let items = SettingKey::ALL
+            .iter()
+            .map(|key| MenuItem::new(*key, "").description(key.definition().description))
+            .collect();
+        let mut screen = SettingsScreen {
+            menu: Menu::new(items),
+            settings,
+            editing: None,
+        };
+        screen.update_labels();
+        screen
     }
 
-    fn render_menu_body(&self, frame: &mut Frame, rect: Rect) {
-        let mut menu_body: Vec<Line<'_>> = vec![];
-
-        for (i, item) in SETTINGS_ITEMS.iter().enumerate() {
-            menu_body.push(Line::from(""));
-            let mut text = if self.active_menu_index == i as i8 {
-                "> ".to_string()
-            } else {
-                String::new()
+    /// Shows the current values, e.g. "Number of Decks: < 6 >"
+    fn update_labels(&mut self) {
+        let settings = self.settings.borrow();
+        for item in self.menu.items_mut() {
+            let definition = item.value.definition();
+            let value = definition.kind.display(settings.value(item.value));
+            let label = match definition.kind {
+                SettingKind::Text { .. } => format!("{}: {}", definition.label, value),
+                _ => format!("{}: < {} >", definition.label, value),
             };
-
-            text.push_str(item.to_string().as_str());
-
-            if i == 0 {
-                text.push_str(format!(": < {} >", self.number_of_decks_value).as_str());
-            } else if i == 1 {
-                text.push_str(format!(": < {} >", self.number_of_players_value).as_str());
-            }
-
-            if self.active_menu_index == i as i8 {
-                menu_body.push(Line::from(text).fg(Color::Green))
-            } else {
-                menu_body.push(Line::from(text));
-            }
-
+            item.set_label(label);
         }
+    }
 
-        let menu_options = Paragraph::new(menu_body)
-            .bold()
-            .alignment(Alignment::Center)
-            .block(Block::default());
-        frame.render_widget(menu_options, rect);
+    /// Stores `value`, telling the user when it could not be saved
+    fn set(&mut self, key: SettingKey, value: SettingValue) -> ModelResponse {
+        let saved = self.settings.borrow_mut().set(key, value);
+        self.saved(saved)
     }
 
-    fn increment_current_menu_item(&mut self, increment: i8) {
-        let menu_item = SETTINGS_ITEMS.get(self.active_menu_index as usize).unwrap();
-        match menu_item {
-            NumberOfDecks => {
-                if increment < 0 && self.number_of_decks_value < 2 {
-                    return;
-                }
-                self.number_of_decks_value += increment;
-            }
-            NumberOfPlayers => {
-                if increment < 0 && self.number_of_players_value < 2 {
-                    return;
-                }
-                self.number_of_players_value += increment;
-            }
+    fn saved(&mut self, saved: std::io::Result<()>) -> ModelResponse {
+        self.update_labels();
+        match saved {
+            Ok(()) => ModelResponse::Refresh,
+            Err(err) => ModelResponse::Toast(format!("Settings not saved: {}", err)),
         }
     }
-}
 
-impl MenuNavigation for SettingsScreen {
-    fn get_menu_length(&self) -> usize {
-        SETTINGS_ITEMS.len()
+    fn step_selected(&mut self, direction: i8) -> ModelResponse {
+        let Some(key) = self.menu.selected() else {
+            return ModelResponse::NoOp;
+        };
+        let value = key.definition().kind.step(self.settings.borrow().value(key), direction);
+        self.set(key, value)
     }
 
-    fn get_menu_index(&self) -> i8 {
-        self.active_menu_index
+    /// Enter flips toggles, moves choices along and opens text settings for editing
+    fn activate(&mut self, key: SettingKey) -> ModelResponse {
+        let definition = key.definition();
+        let current = self.settings.borrow().value(key).clone();
+        match (definition.kind, current) {
+            (SettingKind::Toggle, value) => self.set(key, definition.kind.step(&value, 1)),
+            (SettingKind::Choice { options }, SettingValue::Choice(index)) => {
+                self.set(key, SettingValue::Choice((index + 1) % options.len()))
+            }
+            (SettingKind::Text { max_len }, SettingValue::Text(value)) => {
+                self.editing = Some(key);
+                let message = format!("Up to {} characters", max_len);
+                ModelResponse::OpenModal(Modal::text_input(TEXT_SETTING_MODAL, definition.label, &message, &value))
+            }
+            _ => ModelResponse::NoOp,
+        }
     }
 
-    fn set_menu_index(&mut self, index: i8) {
-        self.active_menu_index = index
+    fn footer_hints(&self) -> Vec<FooterHint> {
+        vec![
+            FooterHint::action(Action::Left),
+            FooterHint::action(Action::Right),
+            FooterHint::action(Action::ResetSetting),
+            FooterHint::action(Action::Back),
+            FooterHint::action(Action::Help),
+        ]
     }
 }
 
 impl Model for SettingsScreen {
-    fn update(&mut self) -> std::io::Result<ModelResponse> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Release {
+    fn update(&mut self, event: &AppEvent) -> std::io::Result<ModelResponse> {
+        if let AppEvent::ModalClosed { id: TEXT_SETTING_MODAL, result } = event {
+            let Some(key) = self.editing.take() else {
+                return Ok(ModelResponse::NoOp);
+            };
+            let (ModalResult::Text(text), SettingKind::Text { max_len }) = (result, key.definition().kind) else {
                 return Ok(ModelResponse::Refresh);
+            };
+            let text = text.trim().chars().take(max_len).collect::<String>();
+            if text.is_empty() {
+                return Ok(ModelResponse::Refresh);
+            }
+            return Ok(self.set(key, SettingValue::Text(text)));
+        }
+        match self.menu.handle(event) {
+            // Clicking one of the arrows changes the value
+            MenuEvent::Clicked(_, Some('<')) => return Ok(self.step_selected(-1)),
+            MenuEvent::Clicked(_, Some('>')) => return Ok(self.step_selected(1)),
+            MenuEvent::Moved | MenuEvent::Clicked(..) => return Ok(ModelResponse::Refresh),
+            MenuEvent::Activated(key) => return Ok(self.activate(key)),
+            MenuEvent::Disabled(_) => return Ok(ModelResponse::NoOp),
+            MenuEvent::Ignored => {}
+        }
+        let AppEvent::Key(key) = event else {
+            return Ok(ModelResponse::NoOp);
+        };
+        match bindings().action(Context::Menu, key) {
+            Some(Action::Quit) => Ok(ModelResponse::Exit),
+            // Return to the Menu
+            Some(Action::MainMenu) => {
+                Ok(ModelResponse::Navigate(Navigation::PopToRoot))
+            }
+            // Back to the previous screen
+            Some(Action::Back) => {
+                Ok(ModelResponse::Navigate(Navigation::Pop))
             }
-            return match key.code {
-                KeyCode::Char('q') => Ok(ModelResponse::Exit),
-                // More cursor down
-                KeyCode::Char('j') | KeyCode::Down => {
-                    self.increment_menu_index(1);
-                    return Ok(ModelResponse::Refresh);
-                }
-                // More cursor up
-                KeyCode::Char('k') | KeyCode::Up => {
-                    self.increment_menu_index(-1);
-                    return Ok(ModelResponse::Refresh);
-                }
-                // Return to the Menu
-                KeyCode::Char('m') => {
-                    return Ok(ModelResponse::NavToMainMenu);
-                }
-                // Increment current value up
-                KeyCode::Char('l') | KeyCode::Right => {
-                    self.increment_current_menu_item(1);
-                    return Ok(ModelResponse::Refresh);
-                }
-                // Increment current value down
-                KeyCode::Char('h') | KeyCode::Left => {
-                    self.increment_current_menu_item(-1);
-                    return Ok(ModelResponse::Refresh);
-                }
-                _ => Ok(ModelResponse::Refresh),
+            // Increment current value up
+            Some(Action::Right) => Ok(self.step_selected(1)),
+            // Increment current value down
+            Some(Action::Left) => Ok(self.step_selected(-1)),
+            Some(Action::ResetSetting) => {
+                let Some(key) = self.menu.selected() else {
+                    return Ok(ModelResponse::NoOp);
+                };
+                let saved = self.settings.borrow_mut().reset(key);
+                Ok(self.saved(saved))
             }
+            _ => Ok(ModelResponse::NoOp),
         }
-        Ok(ModelResponse::Refresh)
     }
 
     fn ui(&mut self, frame: &mut Frame) {
@@ -152,21 +157,89 @@ impl Model for SettingsScreen {
         let screen = frame.area();
         render_border(frame, screen);
 
-        // break the screen into chunks
+        // break the screen into chunks, each setting takes two lines unless
+        // that would push the description into the footer
+        let gap = if title_height(screen) > 4 { 4 } else { 0 };
+        let menu_height = (SettingKey::ALL.len() as u16 * 2).min(screen.height.saturating_sub(title_height(screen) + 1 + gap + 5));
         let menu_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(14),
+                Constraint::Length(title_height(screen)),
+                Constraint::Length(1),
+                Constraint::Length(gap),
+                Constraint::Length(menu_height),
                 Constraint::Length(1),
-                Constraint::Length(4),
-                Constraint::Ratio(1,5),
-                Constraint::Ratio(1,5),
-                Constraint::Ratio(1,5)
+                Constraint::Length(2),
             ])
             .split(screen);
 
         render_title_block(frame, menu_layout[0]);
         render_sub_title_block(frame, menu_layout[1]);
-        self.render_menu_body(frame, menu_layout[3]);
+        self.menu.render(frame, menu_layout[3]);
+        self.menu.render_description(frame, menu_layout[5]);
+
+        let footer = Rect::new(screen.x + 1, screen.bottom().saturating_sub(2), screen.width.saturating_sub(2), 1);
+        render_footer_hints(frame, &self.footer_hints(), footer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyCode;
+    use crate::testing::{Harness, ScriptedEvents};
+
+    fn open_settings() -> ScriptedEvents {
+        ScriptedEvents::new().type_text("s")
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn deck_count_can_be_changed() {
+        let mut harness = Harness::new();
+        harness.run(open_settings().key(KeyCode::Right).key(KeyCode::Right).key(KeyCode::Left));
+        harness.assert_contains("Number of Decks: < 7 >");
+    }
+
+    #[test]
+    fn player_count_never_drops_below_one() {
+        let mut harness = Harness::new();
+        harness.run(open_settings().key(KeyCode::Down).key(KeyCode::Left).key(KeyCode::Left));
+        harness.assert_contains("# of Players: < 1 >");
+    }
+
+    #[test]
+    fn clicking_the_arrows_changes_the_value() {
+        let mut harness = Harness::new();
+        harness.run(open_settings());
+        let mut more = harness.position_of("6 >").unwrap();
+        more.x += 2;
+        harness.run(open_settings().click(more).click(more));
+        harness.assert_contains("Number of Decks: < 8 >");
+    }
+
+    #[test]
+    fn settings_can_be_reset_to_their_default() {
+        let mut harness = Harness::new();
+        harness.run(open_settings().key(KeyCode::Left).key(KeyCode::Left).type_text("r"));
+        harness.assert_contains("Number of Decks: < 6 >");
+    }
+
+    #[test]
+    fn toggles_flip_and_text_is_edited_in_a_dialog() {
+        let mut harness = Harness::new();
+        harness.run(open_settings().type_text("jjjj").key(KeyCode::Enter));
+        harness.assert_contains("Confirm Quit Mid-Hand: < Off >");
+
+        harness.run(
+            open_settings()
+                .type_text("jjjjj")
+                .key(KeyCode::Enter)
+                .key(KeyCode::Backspace)
+                .key(KeyCode::Backspace)
+                .key(KeyCode::Backspace)
+                .key(KeyCode::Backspace)
+                .type_text("Ada")
+                .key(KeyCode::Enter),
+        );
+        harness.assert_contains("Player Name: Ada");
+    }
+}
@@ -0,0 +1,58 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Stderr, Write};
+use std::panic;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::cursor::Show;
+use ratatui::crossterm::event::{DisableMouseCapture, EnableMouseCapture};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
+use ratatui::Terminal;
+use crate::paths::crash_log_path;
+
+pub type Tui = Terminal<CrosstermBackend<Stderr>>;
+
+pub fn init() -> io::Result<Tui> {
+    enable_raw_mode()?;
+    let mut stderr = io::stderr(); // This is a special case. Normally using stdout is fine
+    execute!(stderr, EnterAlternateScreen, EnableMouseCapture)?;
+    let backend = CrosstermBackend::new(stderr);
+    Terminal::new(backend)
+}
+
+/// Puts the terminal back the way we found it. Safe to call more than once.
+pub fn restore() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture, Show)
+}
+
+/// Makes sure a panic never leaves the terminal in raw mode. The terminal is
+/// restored before the panic message is printed, and a crash report is
+/// appended to the crash log.
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore();
+        let report = format!("{}\n\n{}", info, std::backtrace::Backtrace::force_capture());
+        if let Ok(path) = write_crash_report(&report) {
+            eprintln!("The app crashed, a report was written to {}", path.display());
+        }
+        default_hook(info);
+    }));
+}
+
+/// Appends `report` to the crash log and returns where it was written
+pub fn write_crash_report(report: &str) -> io::Result<PathBuf> {
+    let path = crash_log_path();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "=== crash at {} (unix time) ===\n{}\n", seconds, report)?;
+    Ok(path)
+}
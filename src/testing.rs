@@ -0,0 +1,126 @@
+//! Headless helpers for driving the app in tests: a scripted `EventSource`
+//! and a harness that renders into ratatui's `TestBackend`.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io;
+use std::rc::Rc;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
+use ratatui::Terminal;
+use crate::app::App;
+use crate::event::{AppEvent, EventSource};
+use crate::game::animation::AnimationSpeed;
+use crate::run_app;
+use crate::settings::config::Settings;
+
+/// A fixed list of events, played back in order. Runs dry once every event was delivered.
+#[derive(Default)]
+pub struct ScriptedEvents {
+    events: VecDeque<AppEvent>,
+}
+
+impl ScriptedEvents {
+    pub fn new() -> ScriptedEvents {
+        ScriptedEvents::default()
+    }
+
+    pub fn key(mut self, code: KeyCode) -> ScriptedEvents {
+        self.events.push_back(AppEvent::Key(KeyEvent::new(code, KeyModifiers::NONE)));
+        self
+    }
+
+    /// One key press per character of `text`
+    pub fn type_text(self, text: &str) -> ScriptedEvents {
+        text.chars().fold(self, |events, ch| events.key(KeyCode::Char(ch)))
+    }
+
+    pub fn click(self, position: Position) -> ScriptedEvents {
+        self.mouse(MouseEventKind::Down(MouseButton::Left), position)
+    }
+
+    /// One notch of the scroll wheel, up for positive `notches` and down for negative
+    pub fn scroll(self, notches: i16) -> ScriptedEvents {
+        let kind = if notches > 0 { MouseEventKind::ScrollUp } else { MouseEventKind::ScrollDown };
+        (0..notches.unsigned_abs()).fold(self, |events, _| events.mouse(kind, Position::default()))
+    }
+
+    fn mouse(mut self, kind: MouseEventKind, position: Position) -> ScriptedEvents {
+        self.events.push_back(AppEvent::Mouse(MouseEvent {
+            kind,
+            column: position.x,
+            row: position.y,
+            modifiers: KeyModifiers::NONE,
+        }));
+        self
+    }
+
+    pub fn ticks(mut self, count: usize) -> ScriptedEvents {
+        self.events.extend(std::iter::repeat_n(AppEvent::Tick, count));
+        self
+    }
+}
+
+impl EventSource for ScriptedEvents {
+    fn next(&mut self) -> io::Result<Option<AppEvent>> {
+        Ok(self.events.pop_front())
+    }
+}
+
+/// Runs the real main loop against a `TestBackend`, starting from the main menu.
+/// Settings start at their defaults and are never saved, and the drills deal
+/// the same cards every run.
+pub struct Harness {
+    terminal: Terminal<TestBackend>,
+    app: App,
+}
+
+impl Harness {
+    pub fn new() -> Harness {
+        Harness::with_size(160, 50)
+    }
+
+    pub fn with_size(width: u16, height: u16) -> Harness {
+        Harness {
+            terminal: Terminal::new(TestBackend::new(width, height)).unwrap(),
+            app: App::new(Rc::new(RefCell::new(Settings::default().with_session_animation_speed(AnimationSpeed::Off).with_session_seed(7)))),
+        }
+    }
+
+    pub fn run(&mut self, mut events: ScriptedEvents) -> &mut Harness {
+        run_app(&mut self.terminal, &mut self.app, &mut events).unwrap();
+        self
+    }
+
+    /// The last rendered frame, one line per terminal row
+    pub fn screen_text(&self) -> String {
+        let buffer = self.terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Where `text` starts on the last rendered frame
+    pub fn position_of(&self, text: &str) -> Option<Position> {
+        self.screen_text().lines().enumerate().find_map(|(y, line)| {
+            let byte = line.find(text)?;
+            Some(Position::new(line[..byte].chars().count() as u16, y as u16))
+        })
+    }
+
+    pub fn assert_contains(&self, text: &str) {
+        let screen = self.screen_text();
+        assert!(screen.contains(text), "expected {:?} on screen:\n{}", text, screen);
+    }
+
+    pub fn assert_not_contains(&self, text: &str) {
+        let screen = self.screen_text();
+        assert!(!screen.contains(text), "did not expect {:?} on screen:\n{}", text, screen);
+    }
+}
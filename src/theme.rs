@@ -0,0 +1,398 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use blackjack_engine::card::Suit;
+use blackjack_engine::hand::HandOutcome;
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use crate::paths::app_dir;
+
+/// Every colour a theme sets. Screens ask the theme for a style instead of
+/// picking colours themselves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Slot {
+    Border,
+    Text,
+    /// Background of the table
+    Felt,
+    /// The selected menu entry
+    Highlight,
+    RedSuit,
+    BlackSuit,
+    Win,
+    Loss,
+    Push,
+    /// Background of the keys in the footer
+    FooterKey,
+    FooterKeyText,
+    FooterLabel,
+    /// Footer hints for actions that can't be taken right now
+    Disabled,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Preset {
+    /// White text on green felt
+    #[default]
+    Classic,
+    /// The terminal's own background
+    Dark,
+    /// Bright colours on black, for low quality screens and low vision
+    HighContrast,
+    /// Blue and orange in place of red and green, from the Okabe-Ito palette
+    ColorBlind,
+}
+
+/// How many colours the terminal can show
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+impl ColorDepth {
+    /// Guesses from `COLORTERM` and `TERM`, assuming the basic 16 when neither says more
+    pub fn detect() -> ColorDepth {
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+        if env::var("TERM").unwrap_or_default().contains("256color") {
+            return ColorDepth::Ansi256;
+        }
+        ColorDepth::Ansi16
+    }
+}
+
+/// The on-disk format, a preset plus per-slot overrides
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ThemeFile {
+    preset: Preset,
+    colors: BTreeMap<Slot, String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Theme {
+    colors: BTreeMap<Slot, Color>,
+    /// Set when `NO_COLOR` is, styles fall back to bold and reversed text
+    monochrome: bool,
+}
+
+impl Theme {
+    pub fn preset(preset: Preset) -> Theme {
+        use Color::*;
+        let colors: Vec<(Slot, Color)> = match preset {
+            Preset::Classic => vec![
+                (Slot::Border, White),
+                (Slot::Text, White),
+                (Slot::Felt, Rgb(0, 85, 40)),
+                (Slot::Highlight, LightYellow),
+                (Slot::RedSuit, LightRed),
+                (Slot::BlackSuit, White),
+                (Slot::Win, LightGreen),
+                (Slot::Loss, LightRed),
+                (Slot::Push, Yellow),
+                (Slot::FooterKey, Gray),
+                (Slot::FooterKeyText, Black),
+                (Slot::FooterLabel, Gray),
+                (Slot::Disabled, DarkGray),
+            ],
+            Preset::Dark => vec![
+                (Slot::Border, White),
+                (Slot::Text, White),
+                (Slot::Felt, Reset),
+                (Slot::Highlight, Green),
+                (Slot::RedSuit, Red),
+                (Slot::BlackSuit, White),
+                (Slot::Win, Green),
+                (Slot::Loss, Red),
+                (Slot::Push, Yellow),
+                (Slot::FooterKey, Gray),
+                (Slot::FooterKeyText, Black),
+                (Slot::FooterLabel, DarkGray),
+                (Slot::Disabled, DarkGray),
+            ],
+            Preset::HighContrast => vec![
+                (Slot::Border, White),
+                (Slot::Text, White),
+                (Slot::Felt, Black),
+                (Slot::Highlight, LightYellow),
+                (Slot::RedSuit, LightRed),
+                (Slot::BlackSuit, White),
+                (Slot::Win, LightGreen),
+                (Slot::Loss, LightRed),
+                (Slot::Push, LightCyan),
+                (Slot::FooterKey, White),
+                (Slot::FooterKeyText, Black),
+                (Slot::FooterLabel, White),
+                (Slot::Disabled, DarkGray),
+            ],
+            Preset::ColorBlind => vec![
+                (Slot::Border, White),
+                (Slot::Text, White),
+                (Slot::Felt, Rgb(0, 45, 90)),
+                (Slot::Highlight, Rgb(240, 228, 66)),
+                (Slot::RedSuit, Rgb(230, 159, 0)),
+                (Slot::BlackSuit, White),
+                (Slot::Win, Rgb(86, 180, 233)),
+                (Slot::Loss, Rgb(213, 94, 0)),
+                (Slot::Push, Rgb(204, 121, 167)),
+                (Slot::FooterKey, Gray),
+                (Slot::FooterKeyText, Black),
+                (Slot::FooterLabel, Gray),
+                (Slot::Disabled, DarkGray),
+            ],
+        };
+        Theme { colors: colors.into_iter().collect(), monochrome: false }
+    }
+
+    /// No colours at all, only the terminal's own
+    pub fn monochrome() -> Theme {
+        let mut theme = Theme::preset(Preset::default());
+        theme.colors.values_mut().for_each(|color| *color = Color::Reset);
+        theme.monochrome = true;
+        theme
+    }
+
+    pub fn color(&self, slot: Slot) -> Color {
+        self.colors.get(&slot).copied().unwrap_or(Color::Reset)
+    }
+
+    fn fg(&self, slot: Slot) -> Style {
+        Style::default().fg(self.color(slot))
+    }
+
+    pub fn border(&self) -> Style {
+        self.fg(Slot::Border)
+    }
+
+    pub fn felt(&self) -> Style {
+        Style::default().bg(self.color(Slot::Felt)).fg(self.color(Slot::Text))
+    }
+
+    pub fn highlight(&self) -> Style {
+        if self.monochrome {
+            return Style::default().add_modifier(Modifier::REVERSED);
+        }
+        self.fg(Slot::Highlight)
+    }
+
+    pub fn suit(&self, suit: &Suit) -> Style {
+        match suit {
+            Suit::Hearts | Suit::Diamonds => self.fg(Slot::RedSuit),
+            Suit::Clubs | Suit::Spades => self.fg(Slot::BlackSuit),
+        }
+    }
+
+    pub fn outcome(&self, outcome: &HandOutcome) -> Style {
+        let style = match outcome {
+            HandOutcome::Win | HandOutcome::Blackjack => self.fg(Slot::Win),
+            HandOutcome::Loss => self.fg(Slot::Loss),
+            HandOutcome::Push => self.fg(Slot::Push),
+        };
+        style.add_modifier(Modifier::BOLD)
+    }
+
+    pub fn footer_key(&self, enabled: bool) -> Style {
+        match (enabled, self.monochrome) {
+            (true, true) => Style::default().add_modifier(Modifier::REVERSED),
+            (false, true) => Style::default().add_modifier(Modifier::DIM),
+            (true, false) => Style::default().bg(self.color(Slot::FooterKey)).fg(self.color(Slot::FooterKeyText)),
+            (false, false) => Style::default().bg(self.color(Slot::Disabled)).fg(self.color(Slot::FooterKeyText)),
+        }
+    }
+
+    pub fn footer_label(&self, enabled: bool) -> Style {
+        if enabled {
+            self.fg(Slot::FooterLabel)
+        } else {
+            self.fg(Slot::Disabled).add_modifier(Modifier::DIM | Modifier::CROSSED_OUT)
+        }
+    }
+
+    /// Swaps every colour for the closest one `depth` can show
+    pub fn for_depth(mut self, depth: ColorDepth) -> Theme {
+        for (slot, color) in self.colors.iter_mut() {
+            *color = match (slot, depth) {
+                (Slot::Felt, ColorDepth::Ansi16) => felt_16(*color),
+                _ => downgrade(*color, depth),
+            };
+        }
+        self
+    }
+
+    fn parse(contents: &str) -> Result<Theme, Vec<String>> {
+        let file: ThemeFile = serde_json::from_str(contents)
+            .map_err(|err| vec![format!("theme.json: {}", err)])?;
+
+        let mut theme = Theme::preset(file.preset);
+        let mut problems = vec![];
+        for (slot, name) in file.colors {
+            match Color::from_str(&name) {
+                Ok(color) => {
+                    theme.colors.insert(slot, color);
+                }
+                Err(_) => problems.push(format!("theme.json: unknown colour {:?} for {:?}", name, slot)),
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(theme)
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Loads the theme file, falling back to the classic theme if it is missing
+    /// or unusable, and fits it to the terminal. `NO_COLOR` overrides everything.
+    /// Returns the theme and any problems worth telling the user about.
+    pub fn load() -> (Theme, Vec<String>) {
+        if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return (Theme::monochrome(), vec![]);
+        }
+        let (theme, problems) = match fs::read_to_string(theme_path()) {
+            Ok(contents) => match Theme::parse(&contents) {
+                Ok(theme) => (theme, vec![]),
+                Err(problems) => (Theme::preset(Preset::default()), problems),
+            },
+            Err(_) => (Theme::preset(Preset::default()), vec![]),
+        };
+        (theme.for_depth(ColorDepth::detect()), problems)
+    }
+}
+
+/// The 16 basic colours with their usual xterm values
+const ANSI_16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// RGB value of one of the 256 indexed colours
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_16[index as usize].1,
+        16..=231 => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            (level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+fn downgrade(color: Color, depth: ColorDepth) -> Color {
+    let rgb = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index) => indexed_rgb(index),
+        // Named colours work everywhere
+        other => return other,
+    };
+    match (depth, color) {
+        (ColorDepth::TrueColor, _) | (ColorDepth::Ansi256, Color::Indexed(_)) => color,
+        (ColorDepth::Ansi256, _) => {
+            let step = |value: u8| ((value as u16 * 5 + 127) / 255) as u8;
+            Color::Indexed(16 + 36 * step(rgb.0) + 6 * step(rgb.1) + step(rgb.2))
+        }
+        (ColorDepth::Ansi16, _) => {
+            let distance = |(r, g, b): (u8, u8, u8)| {
+                (r as i32 - rgb.0 as i32).pow(2) + (g as i32 - rgb.1 as i32).pow(2) + (b as i32 - rgb.2 as i32).pow(2)
+            };
+            ANSI_16.iter().min_by_key(|(_, value)| distance(*value)).map(|(named, _)| *named).unwrap_or(color)
+        }
+    }
+}
+
+/// Felt is too dark for the nearest of the basic 16, that would always be
+/// black. It keeps its hue instead, so the table stays green.
+fn felt_16(color: Color) -> Color {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index) => indexed_rgb(index),
+        other => return other,
+    };
+    let brightest = r.max(g).max(b);
+    // Near black and greys have no hue to keep
+    if brightest < 16 || r.min(g).min(b) as u16 * 4 > brightest as u16 * 3 {
+        return Color::Black;
+    }
+    match brightest {
+        _ if brightest == g => Color::Green,
+        _ if brightest == b => Color::Blue,
+        _ => Color::Red,
+    }
+}
+
+pub fn theme_path() -> PathBuf {
+    app_dir().join("theme.json")
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Sets the theme used for the rest of the session. Only the first call has any effect.
+pub fn init(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// The active theme, the classic one until `init` is called
+pub fn theme() -> &'static Theme {
+    THEME.get_or_init(|| Theme::preset(Preset::default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Color;
+    use super::{ColorDepth, Preset, Slot, Theme};
+
+    #[test]
+    fn overrides_apply_on_top_of_the_preset() {
+        let theme = Theme::parse(r##"{ "preset": "dark", "colors": { "felt": "#003300" } }"##).unwrap();
+        assert_eq!(theme.color(Slot::Felt), Color::Rgb(0, 51, 0));
+        assert_eq!(theme.color(Slot::Highlight), Color::Green);
+
+        let problems = Theme::parse(r#"{ "colors": { "border": "plaid" } }"#).unwrap_err();
+        assert_eq!(problems.len(), 1, "{:?}", problems);
+    }
+
+    #[test]
+    fn limited_terminals_get_the_closest_basic_colour() {
+        let theme = Theme::preset(Preset::ColorBlind).for_depth(ColorDepth::Ansi16);
+        assert_eq!(theme.color(Slot::Highlight), Color::LightYellow);
+        assert_eq!(theme.color(Slot::Felt), Color::Blue);
+        assert_eq!(Theme::preset(Preset::Classic).for_depth(ColorDepth::Ansi16).color(Slot::Felt), Color::Green);
+        assert_eq!(Theme::preset(Preset::HighContrast).for_depth(ColorDepth::Ansi16).color(Slot::Felt), Color::Black);
+
+        let theme = Theme::preset(Preset::Classic).for_depth(ColorDepth::Ansi256);
+        assert!(matches!(theme.color(Slot::Felt), Color::Indexed(_)));
+    }
+
+    #[test]
+    fn monochrome_has_no_colours() {
+        let theme = Theme::monochrome();
+        assert_eq!(theme.color(Slot::Felt), Color::Reset);
+        assert_eq!(theme.color(Slot::Win), Color::Reset);
+    }
+}
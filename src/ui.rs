@@ -1,20 +1,21 @@
 use ratatui::layout::Alignment;
 use ratatui::{
     layout::Rect,
-    style::Style,
     text::Text,
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
 use ratatui::prelude::{Line, Span};
-use ratatui::style::Color;
 use ratatui::widgets::Wrap;
-use crate::constants::TITLE;
+use blackjack_engine::card::Card;
+use crate::constants::{COMPACT_TITLE, TITLE};
+use crate::keybindings::{bindings, Action};
+use crate::theme::theme;
 
 pub fn render_border(frame: &mut Frame, screen: Rect) {
     let border_block = Block::default()
         .borders(Borders::all())
-        .style(Style::default().fg(Color::White));
+        .style(theme().border());
     let border = Paragraph::new(Text::default())
         .alignment(Alignment::Center)
         .block(border_block);
@@ -28,6 +29,14 @@ pub fn render_text(frame: &mut Frame, rect: Rect, text: &str) {
     frame.render_widget(paragraph, rect);
 }
 
+/// Like `render_text`, for text made of differently styled spans
+pub fn render_line(frame: &mut Frame, rect: Rect, line: Line) {
+    let paragraph = Paragraph::new(line)
+        .alignment(Alignment::Center)
+        .block(Block::default());
+    frame.render_widget(paragraph, rect);
+}
+
 pub fn render_bottom_text(frame: &mut Frame, rect: Rect, text: &str) {
     let paragraph = Paragraph::new(text)
         .alignment(Alignment::Left)  // Changed to Left alignment
@@ -64,10 +73,30 @@ pub fn render_bottom_right_text(frame: &mut Frame, rect: Rect, text: &str) {
     frame.render_widget(paragraph, bottom_rect);
 }
 
+/// Smallest terminal the app can be used in, anything smaller gets `render_too_small`
+pub const MIN_WIDTH: u16 = 60;
+pub const MIN_HEIGHT: u16 = 20;
+
+/// The title that fits in `screen`, the ASCII art one needs a large terminal
+fn title_for(screen: Rect) -> &'static str {
+    let art_width = TITLE.lines().map(|line| line.chars().count()).max().unwrap_or(0) as u16;
+    // Leave room for the border and the rest of the menu
+    if screen.width >= art_width + 2 && screen.height >= 36 {
+        TITLE
+    } else {
+        COMPACT_TITLE
+    }
+}
+
+/// Rows taken by the title `render_title_block` draws in `screen`
+pub fn title_height(screen: Rect) -> u16 {
+    title_for(screen).lines().count() as u16
+}
+
 pub fn render_title_block(frame: &mut Frame, rect: Rect) {
     // Just a placeholder method, rendering may happen in a different
     // Impl section
-    let title_paragraph = Paragraph::new(TITLE)
+    let title_paragraph = Paragraph::new(title_for(frame.area()))
         .alignment(Alignment::Center)
         .block(Block::default());
     frame.render_widget(title_paragraph, rect)
@@ -80,43 +109,136 @@ pub fn render_sub_title_block(frame: &mut Frame, rect: Rect) {
     frame.render_widget(sub_title, rect);
 }
 
-pub fn render_footer_spans(frame: &mut Frame, spans: Vec<String>, rect: Rect) {
-    let mut spans = vec![" Q ".to_string(), " Quit ".to_string(),
-    " M ".to_string(), " Menu ".to_string()];
-
-    let styles = [
-        Style::default().bg(Color::Gray).fg(Color::DarkGray),
-        Style::default().fg(Color::DarkGray),
-    ];
-
-    frame.render_widget(
-        Line::from(
-            spans
-                .iter()
-                .enumerate()
-                .map(|(idx, content)| Span::styled(content, styles[idx % 2]))
-                .collect::<Vec<_>>(),
-        ).left_aligned(),
-        rect
-    );
+/// One entry of the footer: a key and what pressing it does
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FooterHint {
+    key: String,
+    label: String,
+    enabled: bool,
 }
 
+impl FooterHint {
+    pub fn new(key: impl Into<String>, label: impl Into<String>) -> FooterHint {
+        FooterHint {
+            key: key.into(),
+            label: label.into(),
+            enabled: true,
+        }
+    }
 
+    /// Hint for `action`, using the first key it is bound to
+    pub fn action(action: Action) -> FooterHint {
+        FooterHint::new(bindings().key_hint(action), action.label())
+    }
 
-pub trait MenuNavigation {
-    fn get_menu_length(&self) -> usize;
-    fn get_menu_index(&self) -> i8;
-    fn set_menu_index(&mut self, index: i8);
+    /// Greys the hint out when the action can't be taken right now
+    pub fn enabled(mut self, enabled: bool) -> FooterHint {
+        self.enabled = enabled;
+        self
+    }
 
-    fn increment_menu_index(&mut self, increment: i8) {
-        let current_index = self.get_menu_index();
-        if increment < 0 && self.get_menu_index() <= 0 {
-            return
+    fn spans(&self, with_label: bool) -> Vec<Span<'static>> {
+        let mut spans = vec![Span::styled(format!(" {} ", self.key), theme().footer_key(self.enabled))];
+        if with_label {
+            spans.push(Span::styled(format!(" {} ", self.label), theme().footer_label(self.enabled)));
         }
-        if increment > 0 && self.get_menu_index() >= (self.get_menu_length() - 1) as i8 {
-            return
+        spans
+    }
+}
+
+/// Lays `hints` out over as many lines of `width` columns as they need. When
+/// that takes more than `max_lines`, the labels are dropped and only the keys
+/// are shown, anything still left over is cut off.
+pub fn footer_lines(hints: &[FooterHint], width: u16, max_lines: u16) -> Vec<Line<'static>> {
+    let wrap = |with_label: bool| {
+        let mut lines: Vec<Vec<Span<'static>>> = vec![];
+        let mut line_width = 0;
+        for hint in hints {
+            let spans = hint.spans(with_label);
+            let hint_width: usize = spans.iter().map(Span::width).sum();
+            match lines.last_mut() {
+                Some(line) if line_width + hint_width <= width as usize => line.extend(spans),
+                _ => {
+                    lines.push(spans);
+                    line_width = 0;
+                }
+            }
+            line_width += hint_width;
         }
-        self.set_menu_index(current_index + increment);
+        lines
+    };
+
+    let mut lines = wrap(true);
+    if lines.len() > max_lines as usize {
+        lines = wrap(false);
+    }
+    lines.truncate(max_lines as usize);
+    lines.into_iter().map(Line::from).collect()
+}
+
+/// Drawn in place of the current screen while the terminal is below `MIN_WIDTH` x `MIN_HEIGHT`
+pub fn render_too_small(frame: &mut Frame) {
+    let screen = frame.area();
+    let message = vec![
+        Line::from("Terminal too small"),
+        Line::from(""),
+        Line::from(format!("{} x {}, needs at least {} x {}", screen.width, screen.height, MIN_WIDTH, MIN_HEIGHT)),
+    ];
+    let paragraph = Paragraph::new(message)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true })
+        .block(Block::default());
+    frame.render_widget(paragraph, centered_rect(screen, screen.width, 3));
+}
+
+/// Whether `area` is at least `MIN_WIDTH` x `MIN_HEIGHT`
+pub fn fits_minimum_size(area: Rect) -> bool {
+    area.width >= MIN_WIDTH && area.height >= MIN_HEIGHT
+}
+
+pub fn render_footer_hints(frame: &mut Frame, hints: &[FooterHint], rect: Rect) {
+    let lines = footer_lines(hints, rect.width, rect.height);
+    frame.render_widget(Paragraph::new(lines).left_aligned(), rect);
+}
+
+/// Column where centred text of `width` columns starts in `rect`, as `Alignment::Center` puts it
+pub fn centered_x(rect: Rect, width: u16) -> u16 {
+    rect.x + rect.width.saturating_sub(width) / 2
+}
 
+/// Draws `labels` as a centred row of buttons and returns where each one ended up.
+/// Disabled buttons are greyed out like footer hints.
+pub fn render_buttons(frame: &mut Frame, rect: Rect, labels: &[(String, bool)]) -> Vec<Rect> {
+    let mut spans = vec![];
+    let mut rects = vec![];
+    let total_width: u16 = labels.iter().map(|(label, _)| label.chars().count() as u16 + 3).sum();
+    let mut x = centered_x(rect, total_width.saturating_sub(1));
+    for (label, enabled) in labels {
+        let text = format!(" {} ", label);
+        let width = text.chars().count() as u16;
+        rects.push(Rect { x, y: rect.y, width, height: 1 }.intersection(rect));
+        spans.push(Span::styled(text, theme().footer_key(*enabled)));
+        spans.push(Span::raw(" "));
+        x += width + 1;
     }
+    spans.pop();
+    render_line(frame, rect, Line::from(spans));
+    rects
+}
+
+/// A `width` x `height` rect in the middle of `area`, shrunk to fit if needed
+pub fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// A card, coloured by its suit
+pub fn card_span(card: &Card) -> Span<'static> {
+    Span::styled(card.to_string(), theme().suit(&card.suit))
 }